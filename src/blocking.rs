@@ -0,0 +1,168 @@
+//! # Blocking API
+//! Sync versions of the `power`, `screen`, `battery` and `extensions` modules, with the same
+//! function names, for consumers that don't run an async runtime.
+//! Watchers return an iterator that blocks until the next value. The futures run on the
+//! runtime picked by the `async-io` or `tokio` feature, so don't call these from inside an
+//! async task.
+use futures_util::{Stream, StreamExt};
+
+use crate::runtime::block_on;
+
+fn block_on_stream<S: Stream>(stream: S) -> impl Iterator<Item = S::Item> {
+    let mut stream = Box::pin(stream);
+    std::iter::from_fn(move || block_on(stream.next()))
+}
+
+pub mod power {
+    use crate::error::Result;
+    use crate::handlers::easy_gnome::power;
+    use crate::handlers::easy_gnome::PowerProfile;
+
+    pub use crate::handlers::easy_gnome::power::{
+        is_battery_saver_enabled, schedule, set_battery_saver, start_auto_battery_saver,
+        BatterySaverConfig, BatterySaverGuard, InhibitGuard, InhibitMode, PowerAction,
+        PowerCapability, ScheduledAction, SleepEvent,
+    };
+
+    use super::{block_on, block_on_stream};
+
+    pub fn power_off() -> Result<()> {
+        block_on(power::power_off())
+    }
+    pub fn suspend() -> Result<()> {
+        block_on(power::suspend())
+    }
+    pub fn reboot() -> Result<()> {
+        block_on(power::reboot())
+    }
+    pub fn can_power_off() -> Result<PowerCapability> {
+        block_on(power::can_power_off())
+    }
+    pub fn can_suspend() -> Result<PowerCapability> {
+        block_on(power::can_suspend())
+    }
+    pub fn can_reboot() -> Result<PowerCapability> {
+        block_on(power::can_reboot())
+    }
+    pub fn can_hibernate() -> Result<PowerCapability> {
+        block_on(power::can_hibernate())
+    }
+    pub fn is_power_profiles_available() -> bool {
+        block_on(power::is_power_profiles_available())
+    }
+    pub fn get_power_profile() -> Result<PowerProfile> {
+        block_on(power::get_power_profile())
+    }
+    pub fn set_power_profile(profile: PowerProfile) -> Result<()> {
+        block_on(power::set_power_profile(profile))
+    }
+    pub fn inhibit_suspend(who: &str, why: &str, mode: InhibitMode) -> Result<InhibitGuard> {
+        block_on(power::inhibit_suspend(who, why, mode))
+    }
+    pub fn watch_sleep() -> Result<impl Iterator<Item = SleepEvent>> {
+        Ok(block_on_stream(block_on(power::watch_sleep())?))
+    }
+    pub fn watch_power_profile() -> Result<impl Iterator<Item = PowerProfile>> {
+        Ok(block_on_stream(block_on(power::watch_power_profile())?))
+    }
+}
+
+pub mod screen {
+    use crate::error::Result;
+    use crate::handlers::easy_gnome::screen;
+
+    pub use crate::handlers::easy_gnome::screen::{
+        fade_brightness, BrightnessController, Fade, DEFAULT_BRIGHTNESS_INTERVAL,
+    };
+
+    use super::{block_on, block_on_stream};
+
+    pub fn brightness() -> Result<i32> {
+        block_on(screen::brightness())
+    }
+    pub fn set_brightness(brightness: i32) -> Result<()> {
+        block_on(screen::set_brightness(brightness))
+    }
+    pub fn step_up() -> Result<()> {
+        block_on(screen::step_up())
+    }
+    pub fn step_down() -> Result<()> {
+        block_on(screen::step_down())
+    }
+    pub fn watch_brightness() -> Result<impl Iterator<Item = i32>> {
+        Ok(block_on_stream(block_on(screen::watch_brightness())?))
+    }
+}
+
+pub mod battery {
+    use upower_dbus::DeviceProxyBlocking;
+
+    use crate::error::Result;
+    use crate::handlers::easy_gnome::battery;
+
+    pub use crate::handlers::easy_gnome::battery::CriticalAction;
+
+    use super::{block_on, block_on_stream};
+
+    pub fn get_critical_action() -> Result<CriticalAction> {
+        block_on(battery::get_critical_action())
+    }
+    pub fn on_battery() -> Result<bool> {
+        block_on(battery::on_battery())
+    }
+    pub fn get_current_device_battery() -> Result<DeviceProxyBlocking<'static>> {
+        let device = block_on(battery::get_current_device_battery())?;
+        Ok(zbus::blocking::Proxy::from(device.into_inner()).into())
+    }
+    pub fn get_devices_battery() -> Result<Vec<DeviceProxyBlocking<'static>>> {
+        let devices = block_on(battery::get_devices_battery())?;
+        Ok(devices
+            .into_iter()
+            .map(|device| zbus::blocking::Proxy::from(device.into_inner()).into())
+            .collect())
+    }
+    pub fn watch_percentage() -> Result<impl Iterator<Item = f64>> {
+        Ok(block_on_stream(block_on(battery::watch_percentage())?))
+    }
+    pub fn watch_thresholds(thresholds: &[u8]) -> Result<impl Iterator<Item = u8> + '_> {
+        Ok(block_on_stream(block_on(battery::watch_thresholds(
+            thresholds,
+        ))?))
+    }
+}
+
+pub mod extensions {
+    use crate::error::Result;
+    use crate::handlers::easy_gnome::extensions;
+    use crate::handlers::easy_gnome::{ListExtension, ListExtensionState};
+
+    pub use crate::handlers::easy_gnome::extensions::{
+        get_extensions_active, reset_extensions_active, set_extensions_active,
+    };
+
+    use super::{block_on, block_on_stream};
+
+    pub fn is_shell_available() -> bool {
+        block_on(extensions::is_shell_available())
+    }
+    pub fn get_extensions() -> Result<Vec<ListExtension>> {
+        block_on(extensions::get_extensions())
+    }
+    pub fn disable_extension(uuid: &str) -> Result<bool> {
+        block_on(extensions::disable_extension(uuid))
+    }
+    pub fn enable_extension(uuid: &str) -> Result<bool> {
+        block_on(extensions::enable_extension(uuid))
+    }
+    pub fn uninstall_extension(uuid: &str) -> Result<bool> {
+        block_on(extensions::uninstall_extension(uuid))
+    }
+    pub fn open_extension_preferences(uuid: &str) -> Result<()> {
+        block_on(extensions::open_extension_preferences(uuid))
+    }
+    pub fn watch_extension_state() -> Result<impl Iterator<Item = (String, ListExtensionState)>> {
+        Ok(block_on_stream(block_on(
+            extensions::watch_extension_state(),
+        )?))
+    }
+}
@@ -1,20 +1,37 @@
 use std::process::{Command, Output};
 
-pub fn set(key: &str, prop: &str, value: &str) -> Result<(), String> {
+use crate::error::{Error, Result};
+
+/// Error of a gsettings call that exited with an error, e.g. for a key missing from the
+/// installed schema or a value of the wrong type
+fn failed(action: &str, key: &str, prop: &str, stderr: &[u8]) -> Error {
+    Error::Dconf(format!(
+        "Unable to {} {} {}: {}",
+        action,
+        key,
+        prop,
+        String::from_utf8_lossy(stderr).trim()
+    ))
+}
+
+/// ## Set the value of a key
+/// `value` is in GVariant text format, an error is returned when gsettings rejects it.
+pub fn set(key: &str, prop: &str, value: &str) -> Result<()> {
     let mut cmd = Command::new("gsettings");
     cmd.args(&["set", key, prop, value]);
     match cmd.output() {
-        Ok(_) => Ok(()),
-        Err(_) => Err("Unable to set key".to_string()),
+        Ok(Output { status, .. }) if status.success() => Ok(()),
+        Ok(Output { stderr, .. }) => Err(failed("set", key, prop, &stderr)),
+        Err(_) => Err(Error::Dconf("Unable to set key".to_string())),
     }
 }
 /// ## Get the value of a key
-pub fn get(key: &str, prop: &str) -> Result<String, String> {
+pub fn get(key: &str, prop: &str) -> Result<String> {
     let mut cmd = Command::new("gsettings");
     cmd.args(&["get", key, prop]);
     match cmd.output() {
-        Ok(Output { stdout, .. }) => {
-            let stdout_string = String::from_utf8(stdout).unwrap().replace("\n", "");
+        Ok(Output { status, stdout, .. }) if status.success() => {
+            let stdout_string = String::from_utf8_lossy(&stdout).replace("\n", "");
 
             let parts = stdout_string.split(" ").collect::<Vec<&str>>();
 
@@ -23,14 +40,116 @@ pub fn get(key: &str, prop: &str) -> Result<String, String> {
             }
             Ok(stdout_string)
         }
-        Err(_) => Err("Unable to get key".to_string()),
+        Ok(Output { stderr, .. }) => Err(failed("get", key, prop, &stderr)),
+        Err(_) => Err(Error::Dconf("Unable to get key".to_string())),
     }
 }
-pub fn reset(key: &str, prop: &str) -> Result<(), String> {
+pub fn reset(key: &str, prop: &str) -> Result<()> {
     let mut cmd = Command::new("gsettings");
     cmd.args(&["reset", key, prop]);
     match cmd.output() {
-        Ok(_) => Ok(()),
-        Err(_) => Err("Unable to reset key".to_string()),
+        Ok(Output { status, .. }) if status.success() => Ok(()),
+        Ok(Output { stderr, .. }) => Err(failed("reset", key, prop, &stderr)),
+        Err(_) => Err(Error::Dconf("Unable to reset key".to_string())),
+    }
+}
+/// ## Reset every key of a schema and its child schemas to their default
+/// Like `dconf reset -f` on the schema path, keys the crate doesn't manage included.
+pub fn reset_all(key: &str) -> Result<()> {
+    let mut cmd = Command::new("gsettings");
+    cmd.args(&["reset-recursively", key]);
+    match cmd.output() {
+        Ok(Output { status, .. }) if status.success() => Ok(()),
+        Ok(Output { stderr, .. }) => Err(failed("reset", key, "recursively", &stderr)),
+        Err(_) => Err(Error::Dconf(format!("Unable to reset {}", key))),
     }
 }
+/// ## Get the value of a key as printed by gsettings, without any post-processing
+/// Useful for strings and arrays, where `get` would split the value on spaces.
+pub fn get_raw(key: &str, prop: &str) -> Result<String> {
+    let mut cmd = Command::new("gsettings");
+    cmd.args(&["get", key, prop]);
+    match cmd.output() {
+        Ok(Output { status, stdout, .. }) if status.success() => {
+            Ok(String::from_utf8_lossy(&stdout).trim().to_string())
+        }
+        Ok(Output { stderr, .. }) => Err(failed("get", key, prop, &stderr)),
+        Err(_) => Err(Error::Dconf("Unable to get key".to_string())),
+    }
+}
+/// ## Parse a GVariant string (`'value'` or `"value"`) into a plain string
+pub fn parse_string(value: &str) -> String {
+    let mut strings = parse_string_array(value);
+    if strings.is_empty() {
+        return value.trim().to_string();
+    }
+    strings.remove(0)
+}
+/// ## Format a plain string as a GVariant string
+pub fn format_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+/// ## Parse a GVariant string array (`['a', 'b']` or `@as []`) into a vector
+pub fn parse_string_array(value: &str) -> Vec<String> {
+    let mut strings: Vec<String> = Vec::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\'' && c != '"' {
+            continue;
+        }
+        let quote = c;
+        let mut string = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        string.push(escaped);
+                    }
+                }
+                c if c == quote => break,
+                c => string.push(c),
+            }
+        }
+        strings.push(string);
+    }
+    strings
+}
+/// ## Format a vector of strings as a GVariant string array
+pub fn format_string_array(values: &[String]) -> String {
+    if values.is_empty() {
+        return "@as []".to_string();
+    }
+    let values = values
+        .iter()
+        .map(|value| format_string(value))
+        .collect::<Vec<String>>();
+    format!("[{}]", values.join(", "))
+}
+/// ## Get the value of a boolean key
+/// Returns an error when the key doesn't exist in the installed schema (older GNOME
+/// versions) or doesn't hold a boolean.
+pub fn get_bool(key: &str, prop: &str) -> Result<bool> {
+    let value = get(key, prop)?;
+    value
+        .parse::<bool>()
+        .map_err(|_| Error::Parse(format!("Unable to read {} {}", key, prop)))
+}
+/// ## Flip a boolean key
+/// Returns the new value.
+pub fn toggle(key: &str, prop: &str) -> Result<bool> {
+    let value = !get_bool(key, prop)?;
+    set(key, prop, value.to_string().as_str())?;
+    Ok(value)
+}
+/// ## Whether the installed schema has a key
+/// Keys come and go between GNOME versions, `false` when the schema itself is missing.
+pub fn has_key(key: &str, prop: &str) -> bool {
+    gio::SettingsSchemaSource::default()
+        .and_then(|source| source.lookup(key, true))
+        .map(|schema| schema.has_key(prop))
+        .unwrap_or(false)
+}
+/// ## Get the value of a string key, unquoted
+pub fn get_string(key: &str, prop: &str) -> Result<String> {
+    Ok(parse_string(&get_raw(key, prop)?))
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A D-Bus call failed
+    Dbus(zbus::Error),
+    /// Reading or writing a GSettings key failed, holds the reason
+    Dconf(String),
+    /// The service is already busy with the same operation, e.g. another screencast is recording
+    Busy,
+    /// Polkit denied the operation, the user must authenticate as an administrator
+    AuthRequired,
+    /// Location services are disabled or the user refused to share the location
+    LocationDenied,
+    /// The operation isn't available for this target, e.g. uninstalling a distro package
+    NotSupported,
+    /// A GIO call failed
+    Gio(gio::glib::Error),
+    /// Spawning an external command failed
+    Io(std::io::Error),
+    /// An external command exited with an error, holds what it printed to stderr
+    Command(String),
+    /// The user cancelled the operation, e.g. pressed Escape during an area selection
+    Cancelled,
+    /// A file or value couldn't be parsed, holds the reason
+    Parse(String),
+    /// An argument is out of range or refers to something that doesn't exist
+    InvalidArgument(String),
+    /// The operation didn't finish in time, see `timeout::timeout`
+    Timeout,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Dbus(error) => write!(f, "D-Bus error: {}", error),
+            Error::Dconf(reason) => write!(f, "GSettings error: {}", reason),
+            Error::Busy => write!(f, "The service is busy"),
+            Error::AuthRequired => write!(f, "Administrator authentication required"),
+            Error::LocationDenied => write!(f, "Access to the location was denied"),
+            Error::NotSupported => write!(f, "Operation not supported"),
+            Error::Gio(error) => write!(f, "GIO error: {}", error),
+            Error::Io(error) => write!(f, "I/O error: {}", error),
+            Error::Command(stderr) => write!(f, "Command failed: {}", stderr),
+            Error::Cancelled => write!(f, "Cancelled by the user"),
+            Error::Parse(reason) => write!(f, "Parse error: {}", reason),
+            Error::InvalidArgument(reason) => write!(f, "Invalid argument: {}", reason),
+            Error::Timeout => write!(f, "Timed out"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Dbus(error) => Some(error),
+            Error::Gio(error) => Some(error),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// D-Bus errors services reply with when polkit didn't authorize the caller
+const AUTH_ERROR_NAMES: [&str; 3] = [
+    "org.freedesktop.DBus.Error.AccessDenied",
+    "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired",
+    "org.freedesktop.PolicyKit1.Error.NotAuthorized",
+];
+
+fn is_auth_error(error: &zbus::Error) -> bool {
+    match error {
+        zbus::Error::MethodError(name, _, _) => AUTH_ERROR_NAMES.contains(&name.as_str()),
+        zbus::Error::FDO(error) => matches!(
+            **error,
+            zbus::fdo::Error::AccessDenied(_)
+                | zbus::fdo::Error::InteractiveAuthorizationRequired(_)
+        ),
+        _ => false,
+    }
+}
+
+/// D-Bus error of a call that got no reply in time
+const TIMEOUT_ERROR_NAME: &str = "org.freedesktop.DBus.Error.Timeout";
+
+fn is_timeout_error(error: &zbus::Error) -> bool {
+    match error {
+        zbus::Error::MethodError(name, _, _) => name.as_str() == TIMEOUT_ERROR_NAME,
+        zbus::Error::FDO(error) => matches!(**error, zbus::fdo::Error::Timeout(_)),
+        _ => false,
+    }
+}
+
+impl From<zbus::Error> for Error {
+    fn from(error: zbus::Error) -> Self {
+        if is_auth_error(&error) {
+            return Error::AuthRequired;
+        }
+        if is_timeout_error(&error) {
+            return Error::Timeout;
+        }
+        Error::Dbus(error)
+    }
+}
+
+impl From<gio::glib::Error> for Error {
+    fn from(error: gio::glib::Error) -> Self {
+        Error::Gio(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
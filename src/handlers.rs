@@ -2,6 +2,8 @@ pub mod easy_gnome {
     use std::collections::HashMap;
     use zbus::{dbus_proxy, Result};
 
+    use crate::retry::{with_retry, with_retry_unbounded};
+
     #[dbus_proxy(
         interface = "org.freedesktop.login1.Manager",
         default_service = "org.freedesktop.login1",
@@ -11,6 +13,56 @@ pub mod easy_gnome {
         async fn Suspend(&self, arg: bool) -> Result<()>;
         async fn PowerOff(&self, arg: bool) -> Result<()>;
         async fn Reboot(&self, arg: bool) -> Result<()>;
+        async fn Hibernate(&self, arg: bool) -> Result<()>;
+        async fn CanSuspend(&self) -> Result<String>;
+        async fn CanReboot(&self) -> Result<String>;
+        async fn CanPowerOff(&self) -> Result<String>;
+        async fn CanHibernate(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn HandleLidSwitch(&self) -> Result<String>;
+        async fn Inhibit(
+            &self,
+            what: &str,
+            who: &str,
+            why: &str,
+            mode: &str,
+        ) -> Result<zvariant::OwnedFd>;
+        /// Returns (id, uid, user, seat, object path) of every session
+        async fn ListSessions(
+            &self,
+        ) -> Result<Vec<(String, u32, String, String, zvariant::OwnedObjectPath)>>;
+        async fn LockSession(&self, session_id: &str) -> Result<()>;
+        async fn UnlockSession(&self, session_id: &str) -> Result<()>;
+        async fn TerminateSession(&self, session_id: &str) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.login1.Session",
+        default_service = "org.freedesktop.login1"
+    )]
+    trait LoginSession {
+        #[dbus_proxy(property)]
+        fn Active(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn Type(&self) -> Result<String>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.hostname1",
+        default_service = "org.freedesktop.hostname1",
+        default_path = "/org/freedesktop/hostname1"
+    )]
+    trait Hostname {
+        /// The transient hostname, what the kernel currently uses
+        #[dbus_proxy(property)]
+        fn Hostname(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn StaticHostname(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn PrettyHostname(&self) -> Result<String>;
+        fn SetHostname(&self, hostname: &str, interactive: bool) -> Result<()>;
+        fn SetStaticHostname(&self, hostname: &str, interactive: bool) -> Result<()>;
+        fn SetPrettyHostname(&self, hostname: &str, interactive: bool) -> Result<()>;
     }
 
     #[derive(PartialEq, Debug, Clone, Copy)]
@@ -86,6 +138,19 @@ pub mod easy_gnome {
         INITIALIZED = 6,
         UNINSTALLED = 99,
     }
+    impl ListExtensionState {
+        fn from(state: f64) -> ListExtensionState {
+            match state as u32 {
+                1 => ListExtensionState::ENABLED,
+                2 => ListExtensionState::DISABLED,
+                3 => ListExtensionState::ERROR,
+                4 => ListExtensionState::OUT_OF_DATE,
+                5 => ListExtensionState::DOWNLOADING,
+                6 => ListExtensionState::INITIALIZED,
+                _ => ListExtensionState::UNINSTALLED,
+            }
+        }
+    }
     #[derive(Debug)]
     pub struct ListExtension {
         pub uuid: String,
@@ -97,63 +162,18 @@ pub mod easy_gnome {
     }
     impl ExtensionsProxy<'static> {
         async fn launch_extension_prefs(&self, uuid: &str) -> Result<()> {
-            let _reply = self
-                .LaunchExtensionPrefs(uuid.to_string())
-                .await
-                .unwrap_or_else(|_| ());
-            Ok(())
+            with_retry(|| self.LaunchExtensionPrefs(uuid.to_string())).await
         }
-        async fn list_extensions(&self) -> Vec<ListExtension> {
-            let list = self.ListExtensions().await.unwrap();
+        async fn list_extensions(&self) -> Result<Vec<ListExtension>> {
+            let list = with_retry(|| self.ListExtensions()).await?;
             let mut list_extension: Vec<ListExtension> = Vec::new();
-            for extension in list {
-                let uuid = extension.0;
-                let name = extension
-                    .1
-                    .get("name")
-                    .unwrap()
-                    .to_owned()
-                    .try_into()
-                    .unwrap();
-                let description = extension
-                    .1
-                    .get("description")
-                    .unwrap()
-                    .to_owned()
-                    .try_into()
-                    .unwrap();
-                let try_version = extension.1.get("version");
-                let version = match try_version {
-                    Some(version) => version
-                        .to_owned()
-                        .try_into()
-                        .unwrap_or_else(|_| "".to_string()),
-                    None => "".to_string(),
-                };
-                let state_number: f64 = extension
-                    .1
-                    .get("state")
-                    .unwrap()
-                    .to_owned()
-                    .try_into()
-                    .unwrap();
-                let state: ListExtensionState = match state_number {
-                    1.0 => ListExtensionState::ENABLED,
-                    2.0 => ListExtensionState::DISABLED,
-                    3.0 => ListExtensionState::ERROR,
-                    4.0 => ListExtensionState::OUT_OF_DATE,
-                    5.0 => ListExtensionState::DOWNLOADING,
-                    6.0 => ListExtensionState::INITIALIZED,
-                    99.0 => ListExtensionState::UNINSTALLED,
-                    _ => ListExtensionState::UNINSTALLED,
-                };
-                let url = extension
-                    .1
-                    .get("url")
-                    .unwrap()
-                    .to_owned()
-                    .try_into()
-                    .unwrap();
+            for (uuid, info) in list {
+                let name = get_value(&info, "name")?;
+                let description = get_value(&info, "description")?;
+                let version = get_value(&info, "version").unwrap_or_else(|_| "".to_string());
+                let state_number: f64 = get_value(&info, "state")?;
+                let state = ListExtensionState::from(state_number);
+                let url = get_value(&info, "url")?;
                 let item = ListExtension {
                     uuid,
                     name,
@@ -164,9 +184,45 @@ pub mod easy_gnome {
                 };
                 list_extension.push(item);
             }
-            list_extension
+            Ok(list_extension)
         }
     }
+    /// Reads `key` from a D-Bus dict, the error names the key when it's missing or has another type
+    fn get_value<T>(map: &HashMap<String, zvariant::OwnedValue>, key: &str) -> Result<T>
+    where
+        T: TryFrom<zvariant::OwnedValue>,
+        T::Error: std::fmt::Display,
+    {
+        let value = map
+            .get(key)
+            .ok_or_else(|| zbus::Error::Failure(format!("Missing `{}` in D-Bus reply", key)))?;
+        T::try_from(value.to_owned()).map_err(|error| {
+            zbus::Error::Failure(format!("Unexpected type for `{}`: {}", key, error))
+        })
+    }
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Bus {
+        Session,
+        System,
+    }
+    /// ## Check a D-Bus service is running
+    /// Whether something owns `name` on `bus`, so features relying on a missing service
+    /// can be hidden. Returns `false` when the bus can't be reached or `name` isn't valid.
+    pub async fn service_available(bus: Bus, name: &str) -> bool {
+        let name = match zbus::names::BusName::try_from(name) {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+        let connection = match bus {
+            Bus::Session => zbus::Connection::session().await,
+            Bus::System => zbus::Connection::system().await,
+        };
+        let has_owner = async {
+            let proxy = zbus::fdo::DBusProxy::new(&connection?).await?;
+            proxy.name_has_owner(name).await
+        };
+        has_owner.await.unwrap_or(false)
+    }
     // Shell screenshot
     #[dbus_proxy(
         interface = "org.gnome.Shell.Screenshot",
@@ -174,17 +230,292 @@ pub mod easy_gnome {
     )]
     trait Screenshot {
         async fn PickColor(&self) -> Result<HashMap<String, zvariant::OwnedValue>>;
+        async fn Screenshot(
+            &self,
+            include_cursor: bool,
+            flash: bool,
+            filename: &str,
+        ) -> Result<(bool, String)>;
+        async fn ScreenshotArea(
+            &self,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            flash: bool,
+            filename: &str,
+        ) -> Result<(bool, String)>;
+        async fn SelectArea(&self) -> Result<(i32, i32, i32, i32)>;
     }
     impl ScreenshotProxy<'static> {
-        async fn pick_color(&self) -> (f64, f64, f64) {
-            let pick_color = self.PickColor().await.unwrap();
-            let value = pick_color.get("color").unwrap();
-            let (r, g, b): (f64, f64, f64) = value.to_owned().try_into().unwrap();
+        async fn pick_color(&self) -> Result<(f64, f64, f64)> {
+            // The shell waits for the user to click, the default timeout would cut them short
+            let pick_color = with_retry_unbounded(|| self.PickColor()).await?;
+            let (r, g, b): (f64, f64, f64) = get_value(&pick_color, "color")?;
 
-            (r, g, b)
+            Ok((r, g, b))
         }
     }
 
+    #[dbus_proxy(
+        interface = "org.gnome.Shell.Screencast",
+        default_service = "org.gnome.Shell.Screencast",
+        default_path = "/org/gnome/Shell/Screencast"
+    )]
+    trait Screencast {
+        async fn Screencast(
+            &self,
+            file_template: &str,
+            options: HashMap<String, zvariant::OwnedValue>,
+        ) -> Result<(bool, String)>;
+        async fn ScreencastArea(
+            &self,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            file_template: &str,
+            options: HashMap<String, zvariant::OwnedValue>,
+        ) -> Result<(bool, String)>;
+        async fn StopScreencast(&self) -> Result<bool>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.gnome.Shell",
+        default_service = "org.gnome.Shell",
+        default_path = "/org/gnome/Shell"
+    )]
+    trait Shell {
+        #[dbus_proxy(property)]
+        fn ShellVersion(&self) -> Result<String>;
+        async fn Eval(&self, script: String) -> Result<(bool, String)>;
+        #[dbus_proxy(property)]
+        fn OverviewActive(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn set_OverviewActive(&self, active: bool) -> Result<()>;
+        async fn ShowApplications(&self) -> Result<()>;
+        #[dbus_proxy(name = "ShowOSD")]
+        async fn ShowOSD(&self, params: HashMap<&str, zvariant::Value<'_>>) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.gnome.SettingsDaemon.MediaKeys",
+        default_service = "org.gnome.SettingsDaemon.MediaKeys",
+        default_path = "/org/gnome/SettingsDaemon/MediaKeys"
+    )]
+    trait MediaKeys {
+        async fn GrabMediaPlayerKeys(&self, application: &str, time: u32) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.gnome.SettingsDaemon.Rfkill",
+        default_service = "org.gnome.SettingsDaemon.Rfkill",
+        default_path = "/org/gnome/SettingsDaemon/Rfkill"
+    )]
+    trait Rfkill {
+        #[dbus_proxy(property)]
+        fn AirplaneMode(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn set_AirplaneMode(&self, mode: bool) -> Result<()>;
+        #[dbus_proxy(property)]
+        fn HardwareAirplaneMode(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn BluetoothAirplaneMode(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn set_BluetoothAirplaneMode(&self, mode: bool) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.GeoClue2.Manager",
+        default_service = "org.freedesktop.GeoClue2",
+        default_path = "/org/freedesktop/GeoClue2/Manager"
+    )]
+    trait GeoClueManager {
+        async fn GetClient(&self) -> Result<zvariant::OwnedObjectPath>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.GeoClue2.Client",
+        default_service = "org.freedesktop.GeoClue2"
+    )]
+    trait GeoClueClient {
+        async fn Start(&self) -> Result<()>;
+        async fn Stop(&self) -> Result<()>;
+        #[dbus_proxy(property)]
+        fn set_DesktopId(&self, id: &str) -> Result<()>;
+        #[dbus_proxy(property)]
+        fn set_RequestedAccuracyLevel(&self, level: u32) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.GeoClue2.Location",
+        default_service = "org.freedesktop.GeoClue2"
+    )]
+    trait GeoClueLocation {
+        #[dbus_proxy(property)]
+        fn Latitude(&self) -> Result<f64>;
+        #[dbus_proxy(property)]
+        fn Longitude(&self) -> Result<f64>;
+        #[dbus_proxy(property)]
+        fn Accuracy(&self) -> Result<f64>;
+        #[dbus_proxy(property)]
+        fn Altitude(&self) -> Result<f64>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.NetworkManager",
+        default_service = "org.freedesktop.NetworkManager",
+        default_path = "/org/freedesktop/NetworkManager"
+    )]
+    trait NetworkManager {
+        #[dbus_proxy(property)]
+        fn WirelessEnabled(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn set_WirelessEnabled(&self, enabled: bool) -> Result<()>;
+        #[dbus_proxy(property)]
+        fn Connectivity(&self) -> Result<u32>;
+    }
+
+    #[dbus_proxy(
+        interface = "net.hadess.SensorProxy",
+        default_service = "net.hadess.SensorProxy",
+        default_path = "/net/hadess/SensorProxy"
+    )]
+    trait SensorProxy {
+        #[dbus_proxy(property)]
+        fn HasAccelerometer(&self) -> Result<bool>;
+        #[dbus_proxy(property)]
+        fn AccelerometerOrientation(&self) -> Result<String>;
+        async fn ClaimAccelerometer(&self) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.gnome.Mutter.DisplayConfig",
+        default_service = "org.gnome.Mutter.DisplayConfig",
+        default_path = "/org/gnome/Mutter/DisplayConfig"
+    )]
+    trait DisplayConfig {
+        /// Returns (serial, crtcs, outputs, modes, max_screen_width, max_screen_height)
+        #[allow(clippy::type_complexity)]
+        fn GetResources(
+            &self,
+        ) -> Result<(
+            u32,
+            Vec<(
+                u32,
+                i64,
+                i32,
+                i32,
+                i32,
+                i32,
+                i32,
+                u32,
+                Vec<u32>,
+                HashMap<String, zvariant::OwnedValue>,
+            )>,
+            Vec<(
+                u32,
+                i64,
+                i32,
+                Vec<u32>,
+                String,
+                Vec<u32>,
+                Vec<u32>,
+                HashMap<String, zvariant::OwnedValue>,
+            )>,
+            Vec<(u32, i64, u32, u32, f64, u32)>,
+            i32,
+            i32,
+        )>;
+        /// Returns (serial, monitors, logical_monitors, properties)
+        #[allow(clippy::type_complexity)]
+        fn GetCurrentState(
+            &self,
+        ) -> Result<(
+            u32,
+            Vec<(
+                (String, String, String, String),
+                Vec<(
+                    String,
+                    i32,
+                    i32,
+                    f64,
+                    f64,
+                    Vec<f64>,
+                    HashMap<String, zvariant::OwnedValue>,
+                )>,
+                HashMap<String, zvariant::OwnedValue>,
+            )>,
+            Vec<(
+                i32,
+                i32,
+                f64,
+                u32,
+                bool,
+                Vec<(String, String, String, String)>,
+                HashMap<String, zvariant::OwnedValue>,
+            )>,
+            HashMap<String, zvariant::OwnedValue>,
+        )>;
+        fn GetCrtcGamma(&self, serial: u32, crtc: u32) -> Result<(Vec<u16>, Vec<u16>, Vec<u16>)>;
+        fn SetCrtcGamma(
+            &self,
+            serial: u32,
+            crtc: u32,
+            red: Vec<u16>,
+            green: Vec<u16>,
+            blue: Vec<u16>,
+        ) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.ColorManager",
+        default_service = "org.freedesktop.ColorManager",
+        default_path = "/org/freedesktop/ColorManager"
+    )]
+    trait ColorManager {
+        fn GetDevices(&self) -> Result<Vec<zvariant::OwnedObjectPath>>;
+        fn GetProfiles(&self) -> Result<Vec<zvariant::OwnedObjectPath>>;
+        fn FindDeviceById(&self, device_id: &str) -> Result<zvariant::OwnedObjectPath>;
+        fn FindProfileById(&self, profile_id: &str) -> Result<zvariant::OwnedObjectPath>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.ColorManager.Device",
+        default_service = "org.freedesktop.ColorManager"
+    )]
+    trait ColorManagerDevice {
+        #[dbus_proxy(property)]
+        fn DeviceId(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Kind(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Vendor(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Model(&self) -> Result<String>;
+        /// The first one is the default profile
+        #[dbus_proxy(property)]
+        fn Profiles(&self) -> Result<Vec<zvariant::OwnedObjectPath>>;
+        fn MakeProfileDefault(&self, profile: &zvariant::ObjectPath<'_>) -> Result<()>;
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.ColorManager.Profile",
+        default_service = "org.freedesktop.ColorManager"
+    )]
+    trait ColorManagerProfile {
+        #[dbus_proxy(property)]
+        fn ProfileId(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Title(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Filename(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Kind(&self) -> Result<String>;
+        #[dbus_proxy(property)]
+        fn Colorspace(&self) -> Result<String>;
+    }
+
     #[dbus_proxy(
         interface = "org.gnome.SettingsDaemon.Power.Screen",
         default_service = "org.gnome.SettingsDaemon.Power",
@@ -199,453 +530,7130 @@ pub mod easy_gnome {
         fn StepDown(&self) -> Result<()>;
     }
 
+    #[dbus_proxy(
+        interface = "org.gnome.SettingsDaemon.Color",
+        default_service = "org.gnome.SettingsDaemon.Color",
+        default_path = "/org/gnome/SettingsDaemon/Color"
+    )]
+    trait Color {
+        /// Whether the screen is warmed right now, unlike the `night-light-enabled` key
+        /// which only says the schedule is on
+        #[dbus_proxy(property)]
+        fn NightLightActive(&self) -> Result<bool>;
+        /// Hours of the automatic schedule, `-1` while the location is unknown
+        #[dbus_proxy(property)]
+        fn Sunrise(&self) -> Result<f64>;
+        #[dbus_proxy(property)]
+        fn Sunset(&self) -> Result<f64>;
+    }
+
     pub mod power {
+        use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+        use std::sync::Mutex;
+        use std::thread::{self, JoinHandle};
+        use std::time::{Duration, Instant};
+
+        use futures_util::future::{AbortHandle, Abortable};
+        use futures_util::{Stream, StreamExt};
+        use upower_dbus::UPowerProxy;
         use zbus::Connection;
 
-        use crate::handlers::easy_gnome::PowerManagementProxy;
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::screen;
+        use crate::handlers::easy_gnome::{PowerManagementProxy, PowerManagementProxyBlocking};
+        use crate::watch::{watch_property, watch_signal};
 
-        use super::{PowerProfile, PowerProfilesProxy};
+        use super::{service_available, Bus, PowerProfile, PowerProfilesProxy};
 
-        pub async fn power_off() {
-            let connection = Connection::system().await.unwrap();
-            let proxy = PowerManagementProxy::new(&connection).await.unwrap();
-            proxy.PowerOff(true).await.unwrap();
+        /// What an inhibitor blocks
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum InhibitMode {
+            /// Suspend and hibernate, whether requested by the user or automatic
+            Sleep,
+            /// Automatic idle actions like the idle suspend or screen lock
+            Idle,
         }
-        pub async fn suspend() {
-            let connection = Connection::system().await.unwrap();
-            let proxy = PowerManagementProxy::new(&connection).await.unwrap();
-            proxy.Suspend(true).await.unwrap();
-        }
-        pub async fn reboot() {
-            let connection = Connection::system().await.unwrap();
-            let proxy = PowerManagementProxy::new(&connection).await.unwrap();
-            proxy.Reboot(true).await.unwrap();
+        impl InhibitMode {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    InhibitMode::Sleep => "sleep",
+                    InhibitMode::Idle => "idle",
+                }
+            }
         }
-        pub async fn get_power_profile() -> PowerProfile {
-            let connection = Connection::system().await.unwrap();
-            let proxy = PowerProfilesProxy::new(&connection).await.unwrap();
-            PowerProfile::from(proxy.ActiveProfile().await.unwrap().as_str())
+
+        /// A logind inhibitor lock, released when the guard is dropped
+        #[derive(Debug)]
+        pub struct InhibitGuard {
+            _fd: zvariant::OwnedFd,
         }
-        pub async fn set_power_profile(profile: PowerProfile) {
-            let connection = Connection::system().await.unwrap();
-            let proxy = PowerProfilesProxy::new(&connection).await.unwrap();
-            proxy
-                .set_ActiveProfile(profile.as_str().to_string())
-                .await
-                .unwrap();
+        impl InhibitGuard {
+            /// Releases the lock, same as dropping the guard
+            pub fn release(self) {}
         }
-    }
 
-    pub mod screenshot {
-        use zbus::Connection;
-
-        use crate::handlers::easy_gnome::ScreenshotProxy;
-
-        pub async fn pick_color() -> (f64, f64, f64) {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ScreenshotProxy::new(&connection).await.unwrap();
-            proxy.pick_color().await
+        /// Whether logind allows a power action for the current user
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum PowerCapability {
+            Yes,
+            No,
+            /// Allowed after authenticating as an administrator
+            Challenge,
+            /// Not supported by the hardware or system, e.g. hibernate without swap
+            NotAvailable,
+        }
+        impl PowerCapability {
+            fn from(capability: &str) -> PowerCapability {
+                match capability {
+                    "yes" => PowerCapability::Yes,
+                    "challenge" => PowerCapability::Challenge,
+                    "na" => PowerCapability::NotAvailable,
+                    _ => PowerCapability::No,
+                }
+            }
         }
-    }
-
-    pub mod screen {
-        use zbus::Connection;
 
-        use crate::handlers::easy_gnome::ScreenProxy;
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum SleepEvent {
+            /// The system is about to suspend or hibernate
+            Suspending,
+            /// The system is back from sleep
+            Resumed,
+        }
 
-        pub async fn brightness() -> i32 {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ScreenProxy::new(&connection).await.unwrap();
-            proxy.Brightness().await.unwrap()
+        /// Returns `Error::AuthRequired` when polkit refuses, e.g. other users are logged in
+        pub async fn power_off() -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            proxy.PowerOff(true).await?;
+            Ok(())
         }
-        pub async fn set_brightness(brightness: i32) {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ScreenProxy::new(&connection).await.unwrap();
-            proxy.set_Brightness(brightness).await.unwrap();
+        /// Returns `Error::AuthRequired` when polkit refuses
+        pub async fn suspend() -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            proxy.Suspend(true).await?;
+            Ok(())
         }
-        pub async fn step_up() {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ScreenProxy::new(&connection).await.unwrap();
-            proxy.StepUp().await.unwrap();
+        /// Returns `Error::AuthRequired` when polkit refuses, e.g. other users are logged in
+        pub async fn reboot() -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            proxy.Reboot(true).await?;
+            Ok(())
         }
-        pub async fn step_down() {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ScreenProxy::new(&connection).await.unwrap();
-            proxy.StepDown().await.unwrap();
+        pub async fn can_power_off() -> Result<PowerCapability> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            Ok(PowerCapability::from(&proxy.CanPowerOff().await?))
         }
-    }
-
-    pub mod nightlight {
-
-        pub fn get_nightlight_active() -> bool {
-            crate::dconf::get(
-                "org.gnome.settings-daemon.plugins.color",
-                "night-light-enabled",
-            )
-            .unwrap()
-            .parse::<bool>()
-            .unwrap()
+        pub async fn can_suspend() -> Result<PowerCapability> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            Ok(PowerCapability::from(&proxy.CanSuspend().await?))
         }
-        pub fn set_nightlight_active(active: bool) {
-            crate::dconf::set(
-                "org.gnome.settings-daemon.plugins.color",
-                "night-light-enabled",
-                active.to_string().as_str(),
-            )
-            .unwrap();
+        pub async fn can_reboot() -> Result<PowerCapability> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            Ok(PowerCapability::from(&proxy.CanReboot().await?))
         }
-        pub fn get_temperature() -> u32 {
-            crate::dconf::get(
-                "org.gnome.settings-daemon.plugins.color",
-                "night-light-temperature",
-            )
-            .unwrap()
-            .parse::<u32>()
-            .unwrap()
+        pub async fn can_hibernate() -> Result<PowerCapability> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            Ok(PowerCapability::from(&proxy.CanHibernate().await?))
         }
-        pub fn reset_temperature() {
-            crate::dconf::reset(
-                "org.gnome.settings-daemon.plugins.color",
-                "night-light-temperature",
-            )
-            .unwrap();
+        /// Whether power-profiles-daemon runs, it's often missing on desktops
+        pub async fn is_power_profiles_available() -> bool {
+            service_available(Bus::System, "net.hadess.PowerProfiles").await
         }
-        pub fn set_temperature(temperature: u32) {
-            crate::dconf::set(
-                "org.gnome.settings-daemon.plugins.color",
-                "night-light-temperature",
-                temperature.to_string().as_str(),
-            )
-            .unwrap();
+        pub async fn get_power_profile() -> Result<PowerProfile> {
+            let connection = Connection::system().await?;
+            let proxy = PowerProfilesProxy::new(&connection).await?;
+            Ok(PowerProfile::from(proxy.ActiveProfile().await?.as_str()))
+        }
+        /// Emits `Suspending` right before the system sleeps and `Resumed` after it wakes up,
+        /// from logind's `PrepareForSleep` signal.
+        ///
+        /// `Resumed` fires once the system is back and logind notices it, devices and network
+        /// may still be coming up, so consumers re-syncing state should tolerate a short delay.
+        /// `Suspending` gives no time to react unless a delay inhibitor is held.
+        pub async fn watch_sleep() -> Result<impl Stream<Item = SleepEvent>> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            let signals = watch_signal::<bool>(&proxy, "PrepareForSleep").await?;
+            Ok(signals.map(|start| {
+                if start {
+                    SleepEvent::Suspending
+                } else {
+                    SleepEvent::Resumed
+                }
+            }))
+        }
+        /// Blocks suspend (or the idle actions) until the returned guard is dropped,
+        /// through logind's `Inhibit`. This is the right way to keep the system awake
+        /// during long operations like a backup, unlike a screensaver inhibit which only
+        /// keeps the screen on.
+        ///
+        /// `who` is a human readable name of the app and `why` the reason, both are shown
+        /// to the user, e.g. by `systemd-inhibit --list`.
+        /// Returns `Error::AuthRequired` when polkit doesn't allow this user to inhibit.
+        pub async fn inhibit_suspend(
+            who: &str,
+            why: &str,
+            mode: InhibitMode,
+        ) -> Result<InhibitGuard> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            let fd = proxy.Inhibit(mode.as_str(), who, why, "block").await?;
+            Ok(InhibitGuard { _fd: fd })
+        }
+        /// Emits the active power profile every time it changes
+        pub async fn watch_power_profile() -> Result<impl Stream<Item = PowerProfile>> {
+            let connection = Connection::system().await?;
+            let proxy = PowerProfilesProxy::new(&connection).await?;
+            let profiles = watch_property::<String>(&proxy, "ActiveProfile").await;
+            Ok(profiles.map(|profile| PowerProfile::from(profile.as_str())))
+        }
+        /// Returns `Error::AuthRequired` when polkit refuses, recent power-profiles-daemon
+        /// versions check the caller
+        pub async fn set_power_profile(profile: PowerProfile) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerProfilesProxy::new(&connection).await?;
+            proxy
+                .set_ActiveProfile(profile.as_str().to_string())
+                .await?;
+            Ok(())
         }
-    }
-
-    pub mod apps {
-
-        use std::io::Cursor;
-        use std::path::PathBuf;
-
-        use gio::glib::{home_dir, GString};
-        use gio::prelude::*;
-        use gio::AppInfo;
-        use gtk::IconTheme;
-        use gtk::{prelude::*, IconLookupFlags};
-        use image::ImageOutputFormat;
 
-        pub struct App {
-            pub name: GString,
-            pub description: Option<GString>,
-            pub icon: Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
-            pub executable: PathBuf,
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum PowerAction {
+            Suspend,
+            Reboot,
+            PowerOff,
+            Hibernate,
         }
-        impl App {
-            pub fn get_name(&self) -> &GString {
-                &self.name
+        impl PowerAction {
+            fn run(&self) -> Result<()> {
+                let connection = zbus::blocking::Connection::system()?;
+                let proxy = PowerManagementProxyBlocking::new(&connection)?;
+                match self {
+                    PowerAction::Suspend => proxy.Suspend(true)?,
+                    PowerAction::Reboot => proxy.Reboot(true)?,
+                    PowerAction::PowerOff => proxy.PowerOff(true)?,
+                    PowerAction::Hibernate => proxy.Hibernate(true)?,
+                }
+                Ok(())
             }
-            pub fn get_description(&self) -> &Option<GString> {
-                &self.description
+        }
+
+        /// What happens when the laptop lid is closed
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum LidAction {
+            Ignore,
+            Lock,
+            Suspend,
+            Hibernate,
+            HybridSleep,
+            SuspendThenHibernate,
+            PowerOff,
+        }
+        impl LidAction {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    LidAction::Ignore => "ignore",
+                    LidAction::Lock => "lock",
+                    LidAction::Suspend => "suspend",
+                    LidAction::Hibernate => "hibernate",
+                    LidAction::HybridSleep => "hybrid-sleep",
+                    LidAction::SuspendThenHibernate => "suspend-then-hibernate",
+                    LidAction::PowerOff => "poweroff",
+                }
             }
-            pub fn get_icon(&self) -> &Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
-                &self.icon
+            fn from(action: &str) -> Option<LidAction> {
+                match action {
+                    "ignore" => Some(LidAction::Ignore),
+                    "lock" => Some(LidAction::Lock),
+                    "suspend" => Some(LidAction::Suspend),
+                    "hibernate" => Some(LidAction::Hibernate),
+                    "hybrid-sleep" => Some(LidAction::HybridSleep),
+                    "suspend-then-hibernate" => Some(LidAction::SuspendThenHibernate),
+                    "poweroff" => Some(LidAction::PowerOff),
+                    _ => None,
+                }
             }
-            pub fn get_base64_icon(&self) -> Option<String> {
-                match &self.icon {
-                    Some(icon) => {
-                        let mut image_data: Vec<u8> = Vec::new();
-                        icon.write_to(&mut Cursor::new(&mut image_data), ImageOutputFormat::Png)
-                            .unwrap();
-                        let res_base64 = base64::encode(image_data);
-                        Some(format!("data:image/png;base64,{}", res_base64))
-                    }
-                    None => None,
+            /// Value of the GNOME key, which only knows a few actions. Its `blank` only turns
+            /// the screen off, it doesn't lock.
+            fn as_gnome_str(&self) -> Option<&'static str> {
+                match self {
+                    LidAction::Ignore => Some("nothing"),
+                    LidAction::Suspend => Some("suspend"),
+                    LidAction::Hibernate => Some("hibernate"),
+                    LidAction::PowerOff => Some("shutdown"),
+                    _ => None,
                 }
             }
-            pub fn launch(&self) -> Result<(), gio::glib::Error> {
-                // Find app by name
-                let __apps = AppInfo::all();
-                __apps
-                    .iter()
-                    .find(|app| app.name().eq_ignore_ascii_case(&self.name))
-                    .unwrap()
-                    .launch(&[], None::<&gio::AppLaunchContext>)
+            fn from_gnome(action: &str) -> Option<LidAction> {
+                match action {
+                    "nothing" => Some(LidAction::Ignore),
+                    "suspend" => Some(LidAction::Suspend),
+                    "hibernate" => Some(LidAction::Hibernate),
+                    "shutdown" => Some(LidAction::PowerOff),
+                    _ => None,
+                }
             }
         }
 
-        pub struct Apps {
-            pub apps: Vec<App>,
+        const GNOME_POWER_SCHEMA: &str = "org.gnome.settings-daemon.plugins.power";
+        /// Removed from the settings daemon in GNOME 3.28, logind handles the lid since
+        const GNOME_LID_KEY: &str = "lid-close-ac-action";
+        const GNOME_LID_BATTERY_KEY: &str = "lid-close-battery-action";
+        const LOGIND_DROP_IN: &str = "/etc/systemd/logind.conf.d/60-gnome-dbus-api-lid.conf";
+
+        /// ## Lid close action
+        /// Read from the `org.gnome.settings-daemon.plugins.power` keys on the old GNOME
+        /// versions that have them, otherwise from logind's `HandleLidSwitch`. Their
+        /// `blank` action has no `LidAction`, it's an `Error::Parse`.
+        pub async fn get_lid_close_action() -> Result<LidAction> {
+            let action = if crate::dconf::has_key(GNOME_POWER_SCHEMA, GNOME_LID_KEY) {
+                let action = crate::dconf::get_string(GNOME_POWER_SCHEMA, GNOME_LID_KEY)?;
+                LidAction::from_gnome(&action)
+            } else {
+                let connection = Connection::system().await?;
+                let proxy = PowerManagementProxy::new(&connection).await?;
+                LidAction::from(&proxy.HandleLidSwitch().await?)
+            };
+            action.ok_or_else(|| Error::Parse("Unknown lid close action".to_string()))
+        }
+        /// ## Set the lid close action
+        /// Writes the GNOME keys (on AC and on battery) when the installed GNOME has them,
+        /// they only know `Ignore`, `Suspend`, `Hibernate` and `PowerOff`.
+        ///
+        /// Otherwise writes `HandleLidSwitch` to a logind drop-in in
+        /// `/etc/systemd/logind.conf.d`, which needs root: without it an `Error::Io` of kind
+        /// `PermissionDenied` is returned, there is no polkit prompt for it. logind reads it on
+        /// its next start or after `systemctl kill -s HUP systemd-logind`.
+        pub async fn set_lid_close_action(action: LidAction) -> Result<()> {
+            if crate::dconf::has_key(GNOME_POWER_SCHEMA, GNOME_LID_KEY) {
+                let value = action.as_gnome_str().ok_or(Error::NotSupported)?;
+                let value = crate::dconf::format_string(value);
+                crate::dconf::set(GNOME_POWER_SCHEMA, GNOME_LID_KEY, &value)?;
+                return crate::dconf::set(GNOME_POWER_SCHEMA, GNOME_LID_BATTERY_KEY, &value);
+            }
+            let config = format!("[Login]\nHandleLidSwitch={}\n", action.as_str());
+            let path = std::path::Path::new(LOGIND_DROP_IN);
+            crate::runtime::unblock(move || {
+                std::fs::create_dir_all(path.parent().unwrap())
+                    .and_then(|_| std::fs::write(path, config))
+            })
+            .await?;
+            Ok(())
         }
 
-        impl Apps {
-            pub fn get_apps(&self) -> &Vec<App> {
-                &self.apps
+        /// A power action waiting for its delay, see `schedule`
+        pub struct ScheduledAction {
+            action: PowerAction,
+            deadline: Instant,
+            cancel: Sender<()>,
+            thread: JoinHandle<Result<()>>,
+        }
+        impl ScheduledAction {
+            pub fn action(&self) -> PowerAction {
+                self.action
+            }
+            /// Time left before the action runs, zero once it ran
+            pub fn remaining(&self) -> Duration {
+                self.deadline.saturating_duration_since(Instant::now())
+            }
+            /// Stops the action from running, does nothing if it already ran
+            pub fn cancel(&self) {
+                let _ = self.cancel.send(());
             }
+            /// Blocks until the action ran, returning its result, or `Error::Cancelled`
+            /// if it was cancelled. A panic of the timer thread is resumed here.
+            pub fn wait(self) -> Result<()> {
+                match self.thread.join() {
+                    Ok(result) => result,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                }
+            }
+        }
 
-            pub fn new() -> Apps {
-                const ICON_SIZE: i32 = 128;
+        /// ## Run a power action after a delay
+        /// For "shut down in 30 minutes" features. The timer runs on a thread of this process,
+        /// so the action is dropped if the process exits before the delay is over. Dropping
+        /// the returned handle doesn't cancel the action, call `cancel`.
+        /// Polkit is checked when the action runs, not now, see `can_power_off` and friends.
+        pub fn schedule(action: PowerAction, delay: Duration) -> ScheduledAction {
+            let (cancel, cancelled) = mpsc::channel::<()>();
+            let deadline = Instant::now() + delay;
+            let thread = thread::spawn(move || {
+                match cancelled.recv_timeout(delay) {
+                    Ok(()) => return Err(Error::Cancelled),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // The handle was dropped, nobody can cancel anymore
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(deadline.saturating_duration_since(Instant::now()))
+                    }
+                }
+                action.run()
+            });
+            ScheduledAction {
+                action,
+                deadline,
+                cancel,
+                thread,
+            }
+        }
 
-                let __apps = AppInfo::all();
-                let icon_theme: IconTheme = IconTheme::default().unwrap();
-                icon_theme.add_resource_path(
-                    format!(
-                        "{}/.local/share/icons/hicolor",
-                        home_dir().to_str().unwrap()
-                    )
-                    .as_str(),
-                );
-                let mut apps: Vec<App> = Vec::new();
+        #[derive(PartialEq, Debug, Clone, Copy, Default)]
+        pub struct BatterySaverConfig {
+            /// Screen brightness in percent while on battery, `None` leaves the screen alone.
+            /// A screen already dimmer than this isn't touched, neither is one without a
+            /// backlight (e.g. on desktops), the profile is still switched then.
+            pub dim_brightness: Option<i32>,
+        }
 
-                for app in &__apps {
-                    if !app.should_show() {
-                        continue;
-                    }
-                    let name = app.name();
-                    let description = app.description();
-                    let icon = app.icon();
-                    let executable = app.executable();
-
-                    if icon.is_none() {
-                        apps.push(App {
-                            name,
-                            description,
-                            icon: None,
-                            executable,
-                        });
-                        continue;
+        /// What the battery saver changed, to put it back once plugged in
+        struct SavedState {
+            profile: Option<PowerProfile>,
+            brightness: Option<i32>,
+        }
+        impl SavedState {
+            async fn apply(config: &BatterySaverConfig) -> SavedState {
+                let mut profile = None;
+                if let Ok(current) = get_power_profile().await {
+                    if set_power_profile(PowerProfile::PowerSaver).await.is_ok() {
+                        profile = Some(current);
                     }
-                    let icon_name = gio::prelude::IconExt::to_string(&icon.unwrap()).unwrap();
-                    // // Transform icon name to pixbuf
-                    let pixbuf = icon_theme
-                        .load_icon(&icon_name, ICON_SIZE, IconLookupFlags::GENERIC_FALLBACK)
-                        .unwrap_or(
-                            icon_theme
-                                .load_icon("info", ICON_SIZE, IconLookupFlags::GENERIC_FALLBACK)
-                                .unwrap(),
-                        );
-
-                    // Pix buf are cuadruplets of u8 (rgba)
-                    let bytes: Vec<u8> = pixbuf.unwrap().read_pixel_bytes().unwrap().to_vec();
-
-                    // Using image library build a png based on cuadruplets (rgba)
-                    let png: image::ImageBuffer<image::Rgba<u8>, Vec<u8>> =
-                        match image::RgbaImage::from_vec(ICON_SIZE as u32, ICON_SIZE as u32, bytes)
+                }
+                let mut brightness = None;
+                if let Some(dim_brightness) = config.dim_brightness {
+                    // No backlight on desktops, the screen is left alone then
+                    if let Ok(current) = screen::brightness().await {
+                        if current > dim_brightness
+                            && screen::set_brightness(dim_brightness).await.is_ok()
                         {
-                            Some(png) => png,
-                            None => continue,
-                        };
-
-                    apps.push(App {
-                        name,
-                        description,
-                        icon: Some(png),
-                        executable,
-                    });
+                            brightness = Some(current);
+                        }
+                    }
+                }
+                SavedState {
+                    profile,
+                    brightness,
+                }
+            }
+            async fn revert(self) {
+                if let Some(profile) = self.profile {
+                    let _ = set_power_profile(profile).await;
+                }
+                if let Some(brightness) = self.brightness {
+                    let _ = screen::set_brightness(brightness).await;
                 }
-                Apps { apps }
             }
         }
-    }
-
-    pub mod battery {
-        use upower_dbus::{DeviceProxy, UPowerProxy};
 
-        // Get devices with battery stats
-        pub async fn get_current_device_battery() -> zbus::Result<DeviceProxy<'static>> {
+        /// Keeps the automatic battery saver running, see `start_auto_battery_saver`
+        pub struct BatterySaverGuard {
+            abort: AbortHandle,
+            thread: Option<JoinHandle<()>>,
+        }
+        impl BatterySaverGuard {
+            /// Stops the battery saver, same as dropping the guard
+            pub fn stop(self) {}
+        }
+        impl Drop for BatterySaverGuard {
+            fn drop(&mut self) {
+                self.abort.abort();
+                if let Some(thread) = self.thread.take() {
+                    let _ = thread.join();
+                }
+            }
+        }
+
+        /// Applies the saver on every switch to battery and reverts it on every switch to AC
+        async fn follow_on_battery(
+            config: BatterySaverConfig,
+            saved: &mut Option<SavedState>,
+            ready: Sender<Result<()>>,
+        ) {
+            let watch = async {
+                let connection = Connection::system().await?;
+                let upower = UPowerProxy::new(&connection).await?;
+                let changes = watch_property::<bool>(&upower, "OnBattery").await;
+                let on_battery = upower.on_battery().await?;
+                Ok::<_, Error>((changes, on_battery))
+            };
+            let (changes, on_battery) = match watch.await {
+                Ok(watch) => watch,
+                Err(error) => {
+                    let _ = ready.send(Err(error));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            let mut states =
+                Box::pin(futures_util::stream::once(async move { on_battery }).chain(changes));
+            while let Some(on_battery) = states.next().await {
+                match (on_battery, saved.take()) {
+                    (true, None) => *saved = Some(SavedState::apply(&config).await),
+                    (false, Some(state)) => state.revert().await,
+                    (_, state) => *saved = state,
+                }
+            }
+        }
+
+        /// ## Automatic battery saver
+        /// Switches to the power saver profile, and dims the screen if `config` says so, as
+        /// soon as the system runs on battery, following UPower's `OnBattery`. Everything is
+        /// put back when AC comes back, or when the guard is dropped.
+        ///
+        /// Runs on a thread of this process. Profiles are skipped without
+        /// power-profiles-daemon, returns an error when UPower can't be reached.
+        pub fn start_auto_battery_saver(config: BatterySaverConfig) -> Result<BatterySaverGuard> {
+            let (abort, registration) = AbortHandle::new_pair();
+            let (ready, started) = mpsc::channel::<Result<()>>();
+            let thread = thread::spawn(move || {
+                crate::runtime::block_on(async {
+                    let mut saved = None;
+                    let follow = follow_on_battery(config, &mut saved, ready);
+                    let _ = Abortable::new(follow, registration).await;
+                    if let Some(state) = saved {
+                        state.revert().await;
+                    }
+                })
+            });
+            let guard = BatterySaverGuard {
+                abort,
+                thread: Some(thread),
+            };
+            started.recv().unwrap_or(Err(Error::Cancelled))?;
+            Ok(guard)
+        }
+
+        static BATTERY_SAVER: Mutex<Option<BatterySaverGuard>> = Mutex::new(None);
+
+        /// Turns the automatic battery saver on or off for the whole process, with the default
+        /// `BatterySaverConfig`. Use `start_auto_battery_saver` to dim the screen too.
+        pub fn set_battery_saver(enabled: bool) -> Result<()> {
+            let mut battery_saver = BATTERY_SAVER.lock().unwrap();
+            if !enabled {
+                *battery_saver = None;
+            } else if battery_saver.is_none() {
+                *battery_saver = Some(start_auto_battery_saver(BatterySaverConfig::default())?);
+            }
+            Ok(())
+        }
+        pub fn is_battery_saver_enabled() -> bool {
+            BATTERY_SAVER.lock().unwrap().is_some()
+        }
+    }
+
+    pub mod screenshot {
+        use std::path::PathBuf;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::time::Duration;
+
+        use zbus::Connection;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::image_utils::{self, ImageFormat};
+        use crate::handlers::easy_gnome::ScreenshotProxy;
+        use crate::handlers::easy_gnome::{clipboard, display};
+        use crate::retry::with_retry;
+        use crate::timeout::timeout;
+
+        /// ## Let the user pick a color on the screen
+        /// Returns the red, green and blue components, from 0.0 to 1.0.
+        pub async fn pick_color() -> Result<(f64, f64, f64)> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenshotProxy::new(&connection).await?;
+            Ok(proxy.pick_color().await?)
+        }
+        /// Same as `pick_color`, `Error::Timeout` when no color was picked within `duration`,
+        /// the picker is closed then
+        pub async fn pick_color_timeout(duration: Duration) -> Result<(f64, f64, f64)> {
+            timeout(duration, pick_color()).await?
+        }
+        fn saved_path((success, filename_used): (bool, String)) -> Result<String> {
+            if !success {
+                return Err(Error::Dbus(zbus::Error::Failure(
+                    "Unable to take the screenshot".to_string(),
+                )));
+            }
+            Ok(filename_used)
+        }
+        /// ## Save a screenshot of the whole screen, with GNOME's flash animation
+        /// `filename` is an absolute path or a name saved in the Pictures folder, the path
+        /// actually used is returned.
+        /// Since GNOME 41 only allowed callers (like the portal) can take screenshots,
+        /// others get an access denied error unless the shell runs in unsafe mode.
+        pub async fn take_screenshot(filename: &str) -> Result<String> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenshotProxy::new(&connection).await?;
+            saved_path(with_retry(|| proxy.Screenshot(false, true, filename)).await?)
+        }
+        /// Same as `take_screenshot`, `Error::Timeout` when the shell doesn't answer
+        /// within `duration`
+        pub async fn take_screenshot_timeout(filename: &str, duration: Duration) -> Result<String> {
+            timeout(duration, take_screenshot(filename)).await?
+        }
+        /// Same as `take_screenshot` without the flash, for rapid captures
+        pub async fn take_screenshot_quiet(filename: &str) -> Result<String> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenshotProxy::new(&connection).await?;
+            saved_path(with_retry(|| proxy.Screenshot(false, false, filename)).await?)
+        }
+        /// Area of the screen in logical pixels
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct Rect {
+            pub x: i32,
+            pub y: i32,
+            pub width: i32,
+            pub height: i32,
+        }
+        /// GNOME Shell replies with a `G_IO_ERROR_CANCELLED` GError, which has no D-Bus name
+        /// of its own
+        const CANCELLED_ERROR_NAME: &str =
+            "org.gtk.GDBus.UnmappedGError.Quark._g_2dio_2derror_2dquark.Code19";
+
+        /// ## Let the user select an area of the screen
+        /// Shows GNOME's area selection and waits for the user to drag a rectangle, which
+        /// can then be passed to `capture_to_memory`.
+        /// Returns `Error::Cancelled` when the user presses Escape.
+        pub async fn select_area() -> Result<Rect> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenshotProxy::new(&connection).await?;
+            let (x, y, width, height) = proxy.SelectArea().await.map_err(|error| match error {
+                zbus::Error::MethodError(ref name, _, _)
+                    if name.as_str() == CANCELLED_ERROR_NAME =>
+                {
+                    Error::Cancelled
+                }
+                error => Error::from(error),
+            })?;
+            Ok(Rect {
+                x,
+                y,
+                width,
+                height,
+            })
+        }
+        /// ## Capture the screen as PNG bytes
+        /// The whole screen when `area` is `None`. The screenshot goes through a temporary
+        /// file that is removed afterwards.
+        pub async fn capture_to_memory(area: Option<Rect>) -> Result<Vec<u8>> {
+            let filename = temp_capture_path("capture");
+            let filename = filename.to_string_lossy();
+            let path = match area {
+                Some(area) => {
+                    take_screenshot_area(area.x, area.y, area.width, area.height, true, &filename)
+                        .await
+                }
+                None => take_screenshot(&filename).await,
+            };
+            read_capture(&filename, path)
+        }
+        /// Unique temporary file for a capture, so concurrent captures don't overwrite
+        /// each other
+        fn temp_capture_path(kind: &str) -> PathBuf {
+            static NEXT_CAPTURE: AtomicU64 = AtomicU64::new(0);
+            std::env::temp_dir().join(format!(
+                "gnome-dbus-api-{}-{}-{}.png",
+                kind,
+                std::process::id(),
+                NEXT_CAPTURE.fetch_add(1, Ordering::Relaxed)
+            ))
+        }
+        /// Reads the capture the shell saved to `path` and removes it, even when the read
+        /// fails. `filename`, the file asked for, is removed too as the shell may have
+        /// written it before failing.
+        fn read_capture(filename: &str, path: Result<String>) -> Result<Vec<u8>> {
+            let bytes = path.and_then(|path| {
+                let bytes = std::fs::read(&path);
+                let _ = std::fs::remove_file(&path);
+                Ok(bytes?)
+            });
+            let _ = std::fs::remove_file(filename);
+            bytes
+        }
+        /// Same as `capture_to_memory`, `Error::Timeout` when the shell doesn't answer
+        /// within `duration`
+        pub async fn capture_to_memory_timeout(
+            area: Option<Rect>,
+            duration: Duration,
+        ) -> Result<Vec<u8>> {
+            timeout(duration, capture_to_memory(area)).await?
+        }
+        /// ## Capture the screen to the clipboard
+        /// Same as `capture_to_memory`, the PNG ends up on the clipboard. GTK must be
+        /// initialized, see the `clipboard` module.
+        pub async fn capture_to_clipboard(area: Option<Rect>) -> Result<()> {
+            let png = capture_to_memory(area).await?;
+            clipboard::set_image(&png)
+        }
+        /// ## Capture one monitor as PNG bytes
+        /// `monitor_index` is the position in `display::list_monitors`, the screenshot goes
+        /// through a temporary file that is removed afterwards.
+        pub async fn capture_monitor(monitor_index: usize) -> Result<Vec<u8>> {
+            let monitors = display::list_monitors().await?;
+            let monitor = monitors.get(monitor_index).ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "No monitor at index {}, there are {}",
+                    monitor_index,
+                    monitors.len()
+                ))
+            })?;
+            let filename = temp_capture_path("monitor");
+            let filename = filename.to_string_lossy();
+            let path = take_screenshot_area(
+                monitor.x,
+                monitor.y,
+                monitor.width,
+                monitor.height,
+                true,
+                &filename,
+            )
+            .await;
+            read_capture(&filename, path)
+        }
+        /// Same as `capture_monitor` encoded as `format`
+        pub async fn capture_monitor_as(
+            monitor_index: usize,
+            format: ImageFormat,
+        ) -> Result<Vec<u8>> {
+            let png = capture_monitor(monitor_index).await?;
+            if format == ImageFormat::Png {
+                return Ok(png);
+            }
+            image_utils::convert(&png, format).map_err(|error| Error::Parse(error.to_string()))
+        }
+        /// Save a screenshot of an area in logical pixels, `flash` plays the animation
+        /// like GNOME does
+        pub async fn take_screenshot_area(
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            flash: bool,
+            filename: &str,
+        ) -> Result<String> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenshotProxy::new(&connection).await?;
+            saved_path(
+                with_retry(|| proxy.ScreenshotArea(x, y, width, height, flash, filename)).await?,
+            )
+        }
+    }
+
+    /// # Screen recording
+    /// Records the screen through GNOME Shell, the same recorder used by the screenshot UI.
+    pub mod screencast {
+        use std::collections::HashMap;
+
+        use zbus::Connection;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::ScreencastProxy;
+
+        /// A running recording.
+        ///
+        /// GNOME Shell ties a recording to the D-Bus connection that started it, so dropping
+        /// the handle without calling `stop` also stops the recording.
+        pub struct ScreencastHandle {
+            proxy: ScreencastProxy<'static>,
+            path: String,
+        }
+        impl ScreencastHandle {
+            /// The file the video is written to
+            pub fn path(&self) -> &str {
+                &self.path
+            }
+            pub async fn stop(self) -> Result<()> {
+                let stopped = self.proxy.StopScreencast().await?;
+                if !stopped {
+                    return Err(Error::Dbus(zbus::Error::Failure(
+                        "Unable to stop the screencast".to_string(),
+                    )));
+                }
+                Ok(())
+            }
+        }
+
+        /// Starts recording the whole screen.
+        ///
+        /// `filename_template` is relative to the Videos folder unless absolute, and can use
+        /// `%d` (date), `%t` (time) and `%%`, the extension is added by the shell.
+        /// Returns `Error::Busy` when the shell refuses to start, which happens when a
+        /// recording is already running.
+        pub async fn start(filename_template: &str) -> Result<ScreencastHandle> {
+            let connection = Connection::session().await?;
+            let proxy = ScreencastProxy::new(&connection).await?;
+            let (success, path) = proxy.Screencast(filename_template, HashMap::new()).await?;
+            if !success {
+                return Err(Error::Busy);
+            }
+            Ok(ScreencastHandle { proxy, path })
+        }
+        /// Same as `start` but only records the given area, in logical pixels
+        pub async fn start_area(
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            filename_template: &str,
+        ) -> Result<ScreencastHandle> {
+            let connection = Connection::session().await?;
+            let proxy = ScreencastProxy::new(&connection).await?;
+            let (success, path) = proxy
+                .ScreencastArea(x, y, width, height, filename_template, HashMap::new())
+                .await?;
+            if !success {
+                return Err(Error::Busy);
+            }
+            Ok(ScreencastHandle { proxy, path })
+        }
+    }
+
+    pub mod screen {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::mpsc::{self, Sender};
+        use std::thread::{self, JoinHandle};
+        use std::time::Duration;
+
+        use futures_util::Stream;
+        use zbus::Connection;
+
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::{ScreenProxy, ScreenProxyBlocking};
+        use crate::watch::watch_property;
+
+        /// Interval a slider would want, fast enough to feel live
+        pub const DEFAULT_BRIGHTNESS_INTERVAL: Duration = Duration::from_millis(50);
+
+        /// Screen brightness in percent, an error without a backlight (e.g. on desktops)
+        pub async fn brightness() -> Result<i32> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenProxy::new(&connection).await?;
+            Ok(proxy.Brightness().await?)
+        }
+        pub async fn set_brightness(brightness: i32) -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenProxy::new(&connection).await?;
+            Ok(proxy.set_Brightness(brightness).await?)
+        }
+        pub async fn step_up() -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenProxy::new(&connection).await?;
+            Ok(proxy.StepUp().await?)
+        }
+        pub async fn step_down() -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenProxy::new(&connection).await?;
+            Ok(proxy.StepDown().await?)
+        }
+        /// Emits the screen brightness every time it changes, e.g. from the brightness keys
+        pub async fn watch_brightness() -> Result<impl Stream<Item = i32>> {
+            let connection = Connection::session().await?;
+            let proxy = ScreenProxy::new(&connection).await?;
+            Ok(watch_property::<i32>(&proxy, "Brightness").await)
+        }
+
+        /// ## Brightness setter for sliders
+        /// Calling `set_brightness` on every slider move floods the settings daemon,
+        /// `set` only queues the value and a background thread writes the latest one
+        /// at most once per interval. The first value is written right away.
+        /// Dropping the controller writes any pending value and stops the thread.
+        pub struct BrightnessController {
+            sender: Sender<i32>,
+        }
+        impl BrightnessController {
+            pub fn new(interval: Duration) -> Result<BrightnessController> {
+                let connection = zbus::blocking::Connection::session()?;
+                let proxy = ScreenProxyBlocking::new(&connection)?;
+                let (sender, receiver) = mpsc::channel::<i32>();
+                thread::spawn(move || {
+                    while let Ok(mut brightness) = receiver.recv() {
+                        // Only the latest of the values sent while waiting matters
+                        while let Ok(latest) = receiver.try_recv() {
+                            brightness = latest;
+                        }
+                        // Nobody to report to, the next value retries a failed write
+                        let _ = proxy.set_Brightness(brightness);
+                        thread::sleep(interval);
+                    }
+                });
+                Ok(BrightnessController { sender })
+            }
+            pub fn set(&self, brightness: i32) {
+                // The thread only stops when the controller is dropped
+                let _ = self.sender.send(brightness);
+            }
+        }
+
+        /// Bumped by every fade, a fade stops as soon as it isn't the latest one
+        static FADE_GENERATION: AtomicU64 = AtomicU64::new(0);
+        const FADE_STEP_INTERVAL: Duration = Duration::from_millis(20);
+
+        /// A running `fade_brightness`
+        pub struct Fade {
+            generation: u64,
+            thread: JoinHandle<()>,
+        }
+        impl Fade {
+            /// Stops the fade at the current brightness, does nothing if a newer fade took over
+            pub fn cancel(&self) {
+                let _ = FADE_GENERATION.compare_exchange(
+                    self.generation,
+                    self.generation + 1,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                );
+            }
+            /// Blocks until the fade reaches the target or is cancelled
+            pub fn wait(self) {
+                let _ = self.thread.join();
+            }
+        }
+
+        /// ## Smoothly change the brightness
+        /// Goes from the current brightness to `target` (0 to 100) in steps spread over
+        /// `duration`, on a background thread. Starting a new fade cancels the running one,
+        /// so e.g. a "dim on idle" fade can be interrupted by a fade back up.
+        pub fn fade_brightness(target: i32, duration: Duration) -> Result<Fade> {
+            let connection = zbus::blocking::Connection::session()?;
+            let proxy = ScreenProxyBlocking::new(&connection)?;
+            let start = proxy.Brightness()?;
+            let target = target.clamp(0, 100);
+            let generation = FADE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+            let distance = target - start;
+            let max_steps = (duration.as_millis() / FADE_STEP_INTERVAL.as_millis()).max(1) as u32;
+            let steps = distance.unsigned_abs().clamp(1, max_steps);
+            let step_duration = duration / steps;
+            let thread = thread::spawn(move || {
+                for step in 1..=steps {
+                    if FADE_GENERATION.load(Ordering::SeqCst) != generation {
+                        return;
+                    }
+                    let brightness = start + distance * step as i32 / steps as i32;
+                    if proxy.set_Brightness(brightness).is_err() {
+                        return;
+                    }
+                    if step < steps {
+                        thread::sleep(step_duration);
+                    }
+                }
+            });
+            Ok(Fade { generation, thread })
+        }
+    }
+
+    pub mod session {
+        use zbus::Connection;
+
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::{
+            HostnameProxy, LoginSessionProxy, PowerManagementProxy, ShellProxy,
+        };
+
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum SessionType {
+            X11,
+            Wayland,
+            Tty,
+            Unknown,
+        }
+        impl SessionType {
+            fn from(session_type: &str) -> SessionType {
+                match session_type.to_lowercase().as_str() {
+                    "x11" => SessionType::X11,
+                    "wayland" => SessionType::Wayland,
+                    "tty" => SessionType::Tty,
+                    _ => SessionType::Unknown,
+                }
+            }
+        }
+
+        /// A logind session, graphical or not, of any user
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct SessionInfo {
+            pub id: String,
+            pub uid: u32,
+            pub user: String,
+            /// Empty for sessions without a seat, like SSH logins
+            pub seat: String,
+            /// Whether the session is in the foreground of its seat
+            pub active: bool,
+            pub session_type: SessionType,
+        }
+
+        /// Version of the running GNOME Shell, e.g. `"45.2"`.
+        /// `None` when GNOME Shell is not running on the session bus.
+        pub async fn gnome_version() -> Option<String> {
+            let connection = Connection::session().await.ok()?;
+            let proxy = ShellProxy::new(&connection).await.ok()?;
+            proxy.ShellVersion().await.ok()
+        }
+        /// Session type as reported by the `XDG_SESSION_TYPE` environment variable
+        pub fn session_type() -> SessionType {
+            match std::env::var("XDG_SESSION_TYPE") {
+                Ok(session_type) => SessionType::from(&session_type),
+                Err(_) => SessionType::Unknown,
+            }
+        }
+        /// Whether the current desktop is GNOME, based on `XDG_CURRENT_DESKTOP`
+        /// (which may hold a list like `ubuntu:GNOME`)
+        pub fn is_gnome() -> bool {
+            match std::env::var("XDG_CURRENT_DESKTOP") {
+                Ok(desktops) => desktops
+                    .split(':')
+                    .any(|desktop| desktop.eq_ignore_ascii_case("GNOME")),
+                Err(_) => false,
+            }
+        }
+        /// Every session logind knows about, from `ListSessions`. Sessions that end while
+        /// they are read are left out.
+        pub async fn list_sessions() -> Result<Vec<SessionInfo>> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            let mut sessions: Vec<SessionInfo> = Vec::new();
+            for (id, uid, user, seat, path) in proxy.ListSessions().await? {
+                let read = async {
+                    let session = LoginSessionProxy::builder(&connection)
+                        .path(path)?
+                        .build()
+                        .await?;
+                    Ok::<_, zbus::Error>((session.Active().await?, session.Type().await?))
+                };
+                if let Ok((active, session_type)) = read.await {
+                    sessions.push(SessionInfo {
+                        id,
+                        uid,
+                        user,
+                        seat,
+                        active,
+                        session_type: SessionType::from(&session_type),
+                    });
+                }
+            }
+            Ok(sessions)
+        }
+        /// Locks the screen of a session, other users' sessions need admin rights and
+        /// return `Error::AuthRequired` otherwise
+        pub async fn lock_session(id: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            proxy.LockSession(id).await?;
+            Ok(())
+        }
+        /// Same rules as `lock_session`
+        pub async fn unlock_session(id: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            proxy.UnlockSession(id).await?;
+            Ok(())
+        }
+        /// Logs a session out, killing its processes. Same rules as `lock_session`
+        pub async fn terminate_session(id: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = PowerManagementProxy::new(&connection).await?;
+            proxy.TerminateSession(id).await?;
+            Ok(())
+        }
+
+        /// The three hostnames systemd keeps
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Hostnames {
+            /// Hostname the kernel currently uses, e.g. set by DHCP, lost on reboot
+            pub transient: String,
+            /// Hostname from `/etc/hostname`, used at boot, empty when not set
+            pub static_hostname: String,
+            /// Free form name shown to users, e.g. `Julian's Laptop`, empty when not set.
+            /// GNOME Settings calls it the device name.
+            pub pretty: String,
+        }
+
+        pub async fn get_hostnames() -> Result<Hostnames> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(Hostnames {
+                transient: proxy.Hostname().await?,
+                static_hostname: proxy.StaticHostname().await?,
+                pretty: proxy.PrettyHostname().await?,
+            })
+        }
+        /// The hostname currently in use (the transient one)
+        pub async fn get_hostname() -> Result<String> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(proxy.Hostname().await?)
+        }
+        pub async fn get_static_hostname() -> Result<String> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(proxy.StaticHostname().await?)
+        }
+        pub async fn get_pretty_hostname() -> Result<String> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(proxy.PrettyHostname().await?)
+        }
+        /// Valid static hostname for a pretty one, like GNOME Settings derives it:
+        /// lowercase letters, digits and dashes, at most 64 characters
+        fn static_hostname_for(pretty: &str) -> String {
+            let hostname: String = pretty
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            let hostname = hostname
+                .split('-')
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<&str>>()
+                .join("-");
+            let hostname: String = hostname.chars().take(64).collect();
+            hostname.trim_end_matches('-').to_string()
+        }
+        /// ## Rename the device
+        /// Like the device name in GNOME Settings: `name` becomes the pretty hostname and a
+        /// valid version of it (`Julian's Laptop` gives `julian-s-laptop`) the static one.
+        /// The static hostname is kept when `name` has no ASCII letter or digit (e.g.
+        /// `日本`), an empty one would remove `/etc/hostname`.
+        /// Polkit may ask for the administrator password, `Error::AuthRequired` is
+        /// returned when it's refused.
+        pub async fn set_hostname(name: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            proxy.SetPrettyHostname(name, true).await?;
+            let static_hostname = static_hostname_for(name);
+            if !static_hostname.is_empty() {
+                proxy.SetStaticHostname(&static_hostname, true).await?;
+            }
+            Ok(())
+        }
+        /// Sets `/etc/hostname`, an empty string removes it. Needs polkit like `set_hostname`.
+        pub async fn set_static_hostname(hostname: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(proxy.SetStaticHostname(hostname, true).await?)
+        }
+        /// Needs polkit like `set_hostname`
+        pub async fn set_pretty_hostname(hostname: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(proxy.SetPrettyHostname(hostname, true).await?)
+        }
+        /// Changes the hostname until the next reboot or DHCP lease.
+        /// Needs polkit like `set_hostname`.
+        pub async fn set_transient_hostname(hostname: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = HostnameProxy::new(&connection).await?;
+            Ok(proxy.SetHostname(hostname, true).await?)
+        }
+    }
+
+    /// # GNOME Shell
+    /// Advanced access to the running GNOME Shell.
+    pub mod shell {
+        use std::collections::HashMap;
+
+        use zbus::Connection;
+        use zvariant::Value;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::{session, ShellProxy};
+
+        /// Runs `js` inside GNOME Shell through `org.gnome.Shell.Eval` and returns
+        /// the success flag and the JSON encoded result.
+        ///
+        /// **This is meant for advanced use only.**
+        /// - Since GNOME 41 `Eval` only works when the shell runs in unsafe mode
+        ///   (enabled from Looking Glass with `global.context.unsafe_mode = true`).
+        ///   Otherwise it returns `(false, "")` for every script, so check
+        ///   `is_eval_allowed()` first.
+        /// - The script runs with the full privileges of the shell, a bad script can
+        ///   freeze or crash the whole session. Never pass untrusted input.
+        /// - The shell internals reachable from a script are not a stable API and can
+        ///   change between GNOME versions, see `session::gnome_version()`.
+        ///
+        /// A `false` flag with a non empty string means the script threw, the string
+        /// holds the error message.
+        pub async fn eval(js: &str) -> Result<(bool, String)> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            Ok(proxy.Eval(js.to_string()).await?)
+        }
+        /// Whether `eval` will actually run scripts, by evaluating a no-op script.
+        pub async fn is_eval_allowed() -> bool {
+            match eval("true").await {
+                Ok((success, _)) => success,
+                Err(_) => false,
+            }
+        }
+        pub async fn is_overview_active() -> Result<bool> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            Ok(proxy.OverviewActive().await?)
+        }
+        pub async fn show_overview() -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            Ok(proxy.set_OverviewActive(true).await?)
+        }
+        pub async fn hide_overview() -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            Ok(proxy.set_OverviewActive(false).await?)
+        }
+        pub async fn toggle_overview() -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            let active = proxy.OverviewActive().await?;
+            Ok(proxy.set_OverviewActive(!active).await?)
+        }
+        /// ## Show an on-screen display
+        /// The popup GNOME shows for the volume or brightness keys, with a themed `icon`
+        /// (e.g. `audio-volume-high-symbolic`), an optional `label` and an optional `level`
+        /// bar from 0 to `max_level` (1.0 unless the level is boosted above 100%).
+        /// Since GNOME 41 the shell only accepts this from allowed callers (like the settings
+        /// daemon) unless it runs in unsafe mode, `Error::NotSupported` is returned otherwise.
+        pub async fn show_osd(
+            icon: &str,
+            label: Option<&str>,
+            level: Option<f64>,
+            max_level: f64,
+        ) -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            let mut params: HashMap<&str, Value> = HashMap::new();
+            params.insert("icon", Value::from(icon));
+            if let Some(label) = label {
+                params.insert("label", Value::from(label));
+            }
+            if let Some(level) = level {
+                params.insert("level", Value::from(level));
+                params.insert("max_level", Value::from(max_level));
+            }
+            proxy
+                .ShowOSD(params)
+                .await
+                .map_err(|error| match Error::from(error) {
+                    Error::AuthRequired => Error::NotSupported,
+                    error => error,
+                })
+        }
+        /// ## Open the overview on the app grid
+        /// Since GNOME 41 the shell only accepts `ShowApplications` from allowed callers,
+        /// `Main.overview.showApps()` is run through `eval` instead when it's allowed.
+        /// `Error::NotSupported` is returned otherwise.
+        pub async fn show_app_grid() -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ShellProxy::new(&connection).await?;
+            match proxy.ShowApplications().await.map_err(Error::from) {
+                Err(Error::AuthRequired) => {}
+                result => return result,
+            }
+            if !is_eval_allowed().await {
+                return Err(Error::NotSupported);
+            }
+            match eval("Main.overview.showApps()").await? {
+                (true, _) => Ok(()),
+                (false, error) if error.is_empty() => Err(Error::NotSupported),
+                (false, error) => Err(Error::Command(error)),
+            }
+        }
+        /// ## Restart GNOME Shell
+        /// Like `Alt+F2 r`, handy to reload an extension under development. Open windows
+        /// are kept.
+        ///
+        /// **X11 only**: on Wayland the shell is the display server, restarting it would end
+        /// the session, so `Error::NotSupported` is returned. It goes through `eval`, so it
+        /// also returns `Error::NotSupported` when the shell doesn't run in unsafe mode.
+        pub async fn reload() -> Result<()> {
+            if session::session_type() == session::SessionType::Wayland {
+                return Err(Error::NotSupported);
+            }
+            // `Meta.restart` takes the context since GNOME 41
+            let (success, error) = eval(
+                "global.context \
+                    ? Meta.restart('Restarting…', global.context) \
+                    : Meta.restart('Restarting…')",
+            )
+            .await?;
+            match (success, error.is_empty()) {
+                (true, _) => Ok(()),
+                (false, true) => Err(Error::NotSupported),
+                (false, false) => Err(Error::Command(error)),
+            }
+        }
+    }
+
+    /// # Windows
+    /// GNOME has no public D-Bus API to inspect windows, so the best available mechanism is used:
+    /// - `shell::eval` when the shell runs in unsafe mode, works on both X11 and Wayland.
+    /// - `xprop` (EWMH properties like `_NET_ACTIVE_WINDOW`) as a fallback on X11.
+    ///
+    /// On Wayland without unsafe mode windows are unreachable and `Error::NotSupported` is returned.
+    ///
+    /// Window ids are Mutter ids when using Eval and X11 window ids when using `xprop`,
+    /// so only use an id with the functions of this module in the same session.
+    pub mod window {
+        use std::process::Command;
+
+        use serde::de::DeserializeOwned;
+        use serde::Deserialize;
+
+        use crate::error::{Error, Result};
+
+        use super::session::{self, SessionType};
+        use super::shell;
+
+        #[derive(Debug, Clone, PartialEq, Deserialize)]
+        pub struct WindowInfo {
+            pub id: u64,
+            pub title: String,
+            /// Window class, e.g. `Gnome-terminal`
+            pub wm_class: String,
+            /// Desktop file id like `org.gnome.Terminal.desktop` when the window could be matched to an app
+            pub app_id: Option<String>,
+            /// Index of the workspace holding the window, `None` when it's shown on all workspaces
+            pub workspace: Option<u32>,
+            pub minimized: bool,
+        }
+
+        /// Declares `describe(window)`, which turns a `Meta.Window` into a `WindowInfo`
+        const DESCRIBE_WINDOW_JS: &str = "const describe = (w) => {
+            const app = imports.gi.Shell.WindowTracker.get_default().get_window_app(w);
+            return {
+                id: w.get_id(),
+                title: w.get_title() ?? '',
+                wm_class: w.get_wm_class() ?? '',
+                app_id: app ? app.get_id() : null,
+                workspace: w.is_on_all_workspaces() ? null : w.get_workspace()?.index() ?? null,
+                minimized: w.minimized,
+            };
+        };";
+
+        async fn eval_json<T: DeserializeOwned>(js: &str) -> Result<T> {
+            let (success, result) = shell::eval(js).await?;
+            if !success {
+                return Err(Error::Command(result));
+            }
+            serde_json::from_str(&result).map_err(|e| Error::Parse(e.to_string()))
+        }
+
+        /// Runs `action` (JS using `w` and `time`) on the window with the given Mutter id
+        async fn eval_window_action(window_id: u64, action: &str) -> Result<()> {
+            let js = format!(
+                "(() => {{
+                    const w = global.display.list_all_windows().find((w) => w.get_id() == {});
+                    if (!w) return false;
+                    const time = global.get_current_time();
+                    {};
+                    return true;
+                }})()",
+                window_id, action
+            );
+            let found: bool = eval_json(&js).await?;
+            if !found {
+                return Err(Error::InvalidArgument(format!(
+                    "No window with id {}",
+                    window_id
+                )));
+            }
+            Ok(())
+        }
+        /// Runs an X11 tool like `wmctrl` or `xdotool`, failing if it's missing or exits with an error
+        fn run_x11_tool(program: &str, args: &[&str]) -> Result<()> {
+            match Command::new(program).args(args).status() {
+                Ok(status) if status.success() => Ok(()),
+                Ok(_) => Err(Error::Command(format!("{} failed", program))),
+                Err(error) => Err(Error::Io(error)),
+            }
+        }
+        fn xprop(args: &[&str]) -> Result<String> {
+            match Command::new("xprop").args(args).output() {
+                Ok(output) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+                Err(error) => Err(Error::Io(error)),
+            }
+        }
+        /// Parses the window id out of `_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007`
+        fn parse_x11_window_id(value: &str) -> Option<u64> {
+            let id = value.trim().trim_start_matches("0x");
+            u64::from_str_radix(id, 16).ok().filter(|id| *id != 0)
+        }
+        fn x11_window_info(id: u64) -> Result<WindowInfo> {
+            let output = xprop(&[
+                "-id",
+                &format!("0x{:x}", id),
+                "_NET_WM_NAME",
+                "WM_CLASS",
+                "_GTK_APPLICATION_ID",
+                "_NET_WM_DESKTOP",
+                "_NET_WM_STATE",
+            ])?;
+            let mut info = WindowInfo {
+                id,
+                title: String::new(),
+                wm_class: String::new(),
+                app_id: None,
+                workspace: None,
+                minimized: false,
+            };
+            for line in output.lines() {
+                let (name, value) = match line.split_once(" = ") {
+                    Some(property) => property,
+                    None => continue,
+                };
+                // xprop prints strings double quoted and escaped like GVariant does
+                let mut values = crate::dconf::parse_string_array(value);
+                if name.starts_with("_NET_WM_NAME") {
+                    info.title = values.pop().unwrap_or_default();
+                } else if name.starts_with("WM_CLASS") {
+                    // WM_CLASS holds "instance", "Class"
+                    info.wm_class = values.pop().unwrap_or_default();
+                } else if name.starts_with("_GTK_APPLICATION_ID") {
+                    info.app_id = values.pop().map(|id| format!("{}.desktop", id));
+                } else if name.starts_with("_NET_WM_DESKTOP") {
+                    // 0xFFFFFFFF means the window is on all workspaces
+                    info.workspace = value.trim().parse::<u32>().ok().filter(|d| *d != u32::MAX);
+                } else if name.starts_with("_NET_WM_STATE") {
+                    info.minimized = value.contains("_NET_WM_STATE_HIDDEN");
+                }
+            }
+            Ok(info)
+        }
+
+        /// The focused window, `None` when no window has the focus
+        pub async fn active_window() -> Result<Option<WindowInfo>> {
+            if shell::is_eval_allowed().await {
+                let js = format!(
+                    "(() => {{ {} const w = global.display.focus_window; return w ? describe(w) : null; }})()",
+                    DESCRIBE_WINDOW_JS
+                );
+                return eval_json(&js).await;
+            }
+            if session::session_type() == SessionType::X11 {
+                let output = xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+                return match output.split('#').nth(1).and_then(parse_x11_window_id) {
+                    Some(id) => Ok(Some(x11_window_info(id)?)),
+                    None => Ok(None),
+                };
+            }
+            Err(Error::NotSupported)
+        }
+        /// All the open windows, most recently used first when using Eval
+        pub async fn list_windows() -> Result<Vec<WindowInfo>> {
+            if shell::is_eval_allowed().await {
+                let js = format!(
+                    "(() => {{ {} return global.display.get_tab_list(imports.gi.Meta.TabList.NORMAL_ALL, null).map(describe); }})()",
+                    DESCRIBE_WINDOW_JS
+                );
+                return eval_json(&js).await;
+            }
+            if session::session_type() == SessionType::X11 {
+                // _NET_CLIENT_LIST(WINDOW): window id # 0x1e00003, 0x2200003
+                let output = xprop(&["-root", "_NET_CLIENT_LIST"])?;
+                let ids = match output.split('#').nth(1) {
+                    Some(ids) => ids.split(',').filter_map(parse_x11_window_id).collect(),
+                    None => Vec::new(),
+                };
+                return ids.into_iter().map(x11_window_info).collect();
+            }
+            Err(Error::NotSupported)
+        }
+        /// Raises and focuses the window, switching to its workspace if needed.
+        /// On X11 without Eval this needs `wmctrl` installed.
+        pub async fn activate(window_id: u64) -> Result<()> {
+            if shell::is_eval_allowed().await {
+                return eval_window_action(window_id, "w.activate(time)").await;
+            }
+            if session::session_type() == SessionType::X11 {
+                return run_x11_tool("wmctrl", &["-i", "-a", &format!("0x{:x}", window_id)]);
+            }
+            Err(Error::NotSupported)
+        }
+        /// Asks the window to close, the app may still show a confirmation dialog.
+        /// On X11 without Eval this needs `wmctrl` installed.
+        pub async fn close(window_id: u64) -> Result<()> {
+            if shell::is_eval_allowed().await {
+                return eval_window_action(window_id, "w.delete(time)").await;
+            }
+            if session::session_type() == SessionType::X11 {
+                return run_x11_tool("wmctrl", &["-i", "-c", &format!("0x{:x}", window_id)]);
+            }
+            Err(Error::NotSupported)
+        }
+        /// On X11 without Eval this needs `xdotool` installed, EWMH has no minimize message.
+        pub async fn minimize(window_id: u64) -> Result<()> {
+            if shell::is_eval_allowed().await {
+                return eval_window_action(window_id, "w.minimize()").await;
+            }
+            if session::session_type() == SessionType::X11 {
+                return run_x11_tool("xdotool", &["windowminimize", &window_id.to_string()]);
+            }
+            Err(Error::NotSupported)
+        }
+        /// On X11 without Eval this needs `wmctrl` installed.
+        pub async fn maximize(window_id: u64) -> Result<()> {
+            if shell::is_eval_allowed().await {
+                return eval_window_action(
+                    window_id,
+                    "w.maximize(imports.gi.Meta.MaximizeFlags.BOTH)",
+                )
+                .await;
+            }
+            if session::session_type() == SessionType::X11 {
+                return run_x11_tool(
+                    "wmctrl",
+                    &[
+                        "-i",
+                        "-r",
+                        &format!("0x{:x}", window_id),
+                        "-b",
+                        "add,maximized_vert,maximized_horz",
+                    ],
+                );
+            }
+            Err(Error::NotSupported)
+        }
+    }
+
+    /// # Sound
+    /// Uses `pactl`, which talks to both PulseAudio and PipeWire (through pipewire-pulse),
+    /// or `wpctl` on PipeWire setups without the pulse server.
+    /// Volumes are floats where `1.0` is 100%.
+    pub mod sound {
+        use std::collections::HashMap;
+        use std::io::{BufRead, BufReader};
+        use std::pin::Pin;
+        use std::process::{Child, ChildStdout, Command, Output, Stdio};
+        use std::sync::Mutex;
+        use std::task::{Context, Poll};
+        use std::thread;
+
+        use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+        use futures_util::{Stream, StreamExt};
+        use serde::Deserialize;
+
+        use crate::error::{Error, Result};
+
+        use crate::handlers::easy_gnome::shell;
+
+        /// Highest volume accepted by the `_with_boost` setters, 150% like GNOME's over-amplification
+        pub const MAX_BOOSTED_VOLUME: f64 = 1.5;
+
+        /// Command line tool the module drives, `pactl` works with PulseAudio and with
+        /// PipeWire's pulse server, `wpctl` with PipeWire alone
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        enum Backend {
+            Pactl,
+            Wpctl,
+        }
+        static BACKEND: Mutex<Option<Backend>> = Mutex::new(None);
+        /// `pactl` when it can reach a server, otherwise `wpctl`
+        fn backend() -> Backend {
+            *BACKEND
+                .lock()
+                .unwrap()
+                .get_or_insert_with(|| match pactl(&["info"]) {
+                    Ok(_) => Backend::Pactl,
+                    Err(_) => Backend::Wpctl,
+                })
+        }
+        /// Runs `call` with the backend, which is checked again after a failure, e.g. when
+        /// the sound server (re)started after the last check
+        fn with_backend<T>(call: impl FnOnce(Backend) -> Result<T>) -> Result<T> {
+            let result = call(backend());
+            if result.is_err() {
+                *BACKEND.lock().unwrap() = None;
+            }
+            result
+        }
+
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        enum Device {
+            Output,
+            Input,
+        }
+        impl Device {
+            fn target(&self, backend: Backend) -> &'static str {
+                match (backend, self) {
+                    (Backend::Pactl, Device::Output) => "@DEFAULT_SINK@",
+                    (Backend::Pactl, Device::Input) => "@DEFAULT_SOURCE@",
+                    (Backend::Wpctl, Device::Output) => "@DEFAULT_AUDIO_SINK@",
+                    (Backend::Wpctl, Device::Input) => "@DEFAULT_AUDIO_SOURCE@",
+                }
+            }
+            /// `pactl` subcommand for `action` (`get-volume`, `set-mute`...) on this device
+            fn pactl_command(&self, action: &str) -> String {
+                let (verb, property) = action.split_once('-').unwrap();
+                match self {
+                    Device::Output => format!("{}-sink-{}", verb, property),
+                    Device::Input => format!("{}-source-{}", verb, property),
+                }
+            }
+        }
+
+        fn run(program: &str, args: &[&str]) -> Result<String> {
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            match cmd.output() {
+                Ok(Output { status, stdout, .. }) if status.success() => {
+                    Ok(String::from_utf8_lossy(&stdout).into_owned())
+                }
+                Ok(Output { stderr, .. }) => Err(Error::Command(
+                    String::from_utf8_lossy(&stderr).trim().to_string(),
+                )),
+                Err(error) => Err(Error::Io(error)),
+            }
+        }
+        fn pactl(args: &[&str]) -> Result<String> {
+            run("pactl", args)
+        }
+        fn wpctl(args: &[&str]) -> Result<String> {
+            run("wpctl", args)
+        }
+        /// Parses the first channel of `Volume: front-left: 65536 / 100% / 0.00 dB, ...`
+        fn parse_volume(output: &str) -> Result<f64> {
+            output
+                .split('/')
+                .map(|part| part.trim())
+                .find(|part| part.ends_with('%'))
+                .and_then(|percentage| percentage.trim_end_matches('%').parse::<f64>().ok())
+                .map(|percentage| percentage / 100.0)
+                .ok_or_else(|| Error::Parse("Unable to parse volume".to_string()))
+        }
+        /// Parses wpctl's `Volume: 0.40` or `Volume: 0.40 [MUTED]`
+        fn parse_wpctl_volume(output: &str) -> Result<f64> {
+            output
+                .split_whitespace()
+                .nth(1)
+                .and_then(|volume| volume.parse::<f64>().ok())
+                .ok_or_else(|| Error::Parse("Unable to parse volume".to_string()))
+        }
+        fn format_volume(volume: f64) -> String {
+            format!("{}%", (volume * 100.0).round())
+        }
+        /// Parses `Mute: yes`
+        fn parse_mute(output: &str) -> bool {
+            output.trim().ends_with("yes")
+        }
+
+        fn get_volume(device: Device) -> Result<f64> {
+            with_backend(|backend| {
+                let target = device.target(backend);
+                match backend {
+                    Backend::Pactl => {
+                        parse_volume(&pactl(&[&device.pactl_command("get-volume"), target])?)
+                    }
+                    Backend::Wpctl => parse_wpctl_volume(&wpctl(&["get-volume", target])?),
+                }
+            })
+        }
+        fn set_volume(device: Device, volume: f64) -> Result<()> {
+            with_backend(|backend| {
+                let target = device.target(backend);
+                match backend {
+                    Backend::Pactl => pactl(&[
+                        &device.pactl_command("set-volume"),
+                        target,
+                        &format_volume(volume),
+                    ])?,
+                    Backend::Wpctl => wpctl(&["set-volume", target, &format!("{:.2}", volume)])?,
+                };
+                Ok(())
+            })
+        }
+        fn is_muted(device: Device) -> Result<bool> {
+            with_backend(|backend| {
+                let target = device.target(backend);
+                match backend {
+                    Backend::Pactl => Ok(parse_mute(&pactl(&[
+                        &device.pactl_command("get-mute"),
+                        target,
+                    ])?)),
+                    Backend::Wpctl => Ok(wpctl(&["get-volume", target])?.contains("[MUTED]")),
+                }
+            })
+        }
+        /// `mute` is `1`, `0` or `toggle`, which both tools understand
+        fn set_mute(device: Device, mute: &str) -> Result<()> {
+            with_backend(|backend| {
+                let target = device.target(backend);
+                match backend {
+                    Backend::Pactl => pactl(&[&device.pactl_command("set-mute"), target, mute])?,
+                    Backend::Wpctl => wpctl(&["set-mute", target, mute])?,
+                };
+                Ok(())
+            })
+        }
+
+        /// Volume of the default output (speakers or headphones)
+        pub fn get_output_volume() -> Result<f64> {
+            get_volume(Device::Output)
+        }
+        /// Sets the volume of the default output, clamped to 0.0–1.0
+        pub fn set_output_volume(volume: f64) -> Result<()> {
+            set_volume(Device::Output, volume.clamp(0.0, 1.0))
+        }
+        pub fn is_output_muted() -> Result<bool> {
+            is_muted(Device::Output)
+        }
+        /// Speaker icon GNOME shows for an output volume
+        fn output_volume_icon(volume: f64, muted: bool) -> &'static str {
+            if muted || volume <= 0.0 {
+                "audio-volume-muted-symbolic"
+            } else if volume > 1.0 {
+                "audio-volume-overamplified-symbolic"
+            } else if volume < 1.0 / 3.0 {
+                "audio-volume-low-symbolic"
+            } else if volume < 2.0 / 3.0 {
+                "audio-volume-medium-symbolic"
+            } else {
+                "audio-volume-high-symbolic"
+            }
+        }
+        /// ## Set the output volume and show GNOME's volume popup
+        /// Same as `set_output_volume` followed by `shell::show_osd` with the speaker icon and
+        /// the new level, like the volume keys do. The volume is set even when the popup
+        /// can't be shown, the error of `show_osd` is returned then.
+        pub async fn set_output_volume_with_osd(volume: f64) -> Result<()> {
+            set_output_volume(volume)?;
+            let volume = get_output_volume()?;
+            let icon = output_volume_icon(volume, is_output_muted()?);
+            shell::show_osd(icon, None, Some(volume), 1.0).await
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct OutputDevice {
+            /// What `set_default_output` takes, a sink name with PulseAudio and a node
+            /// id with PipeWire's `wpctl`
+            pub id: String,
+            /// Name shown in Settings, e.g. `Built-in Audio Analog Stereo`
+            pub description: String,
+            pub is_default: bool,
+        }
+
+        #[derive(Deserialize)]
+        struct PactlSink {
+            name: String,
+            description: String,
+        }
+        /// Node or metadata object of `pw-dump`
+        #[derive(Deserialize)]
+        struct PwObject {
+            id: u32,
+            #[serde(rename = "type")]
+            object_type: String,
+            #[serde(default)]
+            info: Option<serde_json::Value>,
+            #[serde(default)]
+            metadata: Option<Vec<serde_json::Value>>,
+        }
+
+        fn list_pactl_sinks() -> Result<Vec<OutputDevice>> {
+            let default_sink = pactl(&["get-default-sink"])?;
+            let sinks: Vec<PactlSink> =
+                serde_json::from_str(&pactl(&["--format=json", "list", "sinks"])?)
+                    .map_err(|error| Error::Parse(error.to_string()))?;
+            Ok(sinks
+                .into_iter()
+                .map(|sink| OutputDevice {
+                    is_default: sink.name == default_sink.trim(),
+                    id: sink.name,
+                    description: sink.description,
+                })
+                .collect())
+        }
+        /// Sinks from `pw-dump`, which ships with `wpctl` and prints JSON unlike `wpctl status`
+        fn list_pipewire_sinks() -> Result<Vec<OutputDevice>> {
+            let objects: Vec<PwObject> = serde_json::from_str(&run("pw-dump", &[])?)
+                .map_err(|error| Error::Parse(error.to_string()))?;
+            let default_sink = objects
+                .iter()
+                .filter_map(|object| object.metadata.as_ref())
+                .flatten()
+                .find(|entry| entry["key"] == "default.audio.sink")
+                .and_then(|entry| entry["value"]["name"].as_str())
+                .map(|name| name.to_string());
+            Ok(objects
+                .iter()
+                .filter(|object| object.object_type == "PipeWire:Interface:Node")
+                .filter_map(|object| {
+                    let props = &object.info.as_ref()?["props"];
+                    if props["media.class"] != "Audio/Sink" {
+                        return None;
+                    }
+                    let name = props["node.name"].as_str()?;
+                    Some(OutputDevice {
+                        id: object.id.to_string(),
+                        description: props["node.description"]
+                            .as_str()
+                            .unwrap_or(name)
+                            .to_string(),
+                        is_default: default_sink.as_deref() == Some(name),
+                    })
+                })
+                .collect())
+        }
+        /// ## Output devices (sinks)
+        /// Through `pactl` (PulseAudio 16 or PipeWire's pulse server) or else `pw-dump`, no
+        /// audio library is linked.
+        pub fn list_output_devices() -> Result<Vec<OutputDevice>> {
+            with_backend(|backend| match backend {
+                Backend::Pactl => list_pactl_sinks(),
+                Backend::Wpctl => list_pipewire_sinks(),
+            })
+        }
+        /// Makes the device with `id` (see `OutputDevice::id`) the default output, like
+        /// picking it in Settings
+        pub fn set_default_output(id: &str) -> Result<()> {
+            with_backend(|backend| {
+                match backend {
+                    Backend::Pactl => pactl(&["set-default-sink", id])?,
+                    Backend::Wpctl => wpctl(&["set-default", id])?,
+                };
+                Ok(())
+            })
+        }
+
+        /// A stream an app plays (a sink input), one row of a mixer
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct SinkInput {
+            /// Index the sound server gives the stream, stable while it plays
+            pub index: u32,
+            /// `application.name` of the stream, e.g. `Firefox`, empty when the app sets none
+            pub app_name: String,
+            /// Loudest channel, like GNOME's mixer shows it
+            pub volume: f64,
+        }
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum SinkInputEvent {
+            Added(SinkInput),
+            /// Index of the stream that ended
+            Removed(u32),
+            VolumeChanged(SinkInput),
+        }
+
+        #[derive(Deserialize)]
+        struct PactlSinkInput {
+            index: u32,
+            #[serde(default)]
+            properties: HashMap<String, serde_json::Value>,
+            #[serde(default)]
+            volume: HashMap<String, PactlVolume>,
+        }
+        #[derive(Deserialize)]
+        struct PactlVolume {
+            value_percent: String,
+        }
+        impl PactlSinkInput {
+            fn into_sink_input(self) -> SinkInput {
+                let volume = self
+                    .volume
+                    .values()
+                    .filter_map(|channel| {
+                        channel
+                            .value_percent
+                            .trim_end_matches('%')
+                            .parse::<f64>()
+                            .ok()
+                    })
+                    .fold(0.0, f64::max);
+                SinkInput {
+                    index: self.index,
+                    app_name: self
+                        .properties
+                        .get("application.name")
+                        .and_then(|name| name.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    volume: volume / 100.0,
+                }
+            }
+        }
+
+        /// ## Streams of the apps playing sound
+        /// Through `pactl`, `Error::NotSupported` on PipeWire setups without the pulse server.
+        pub fn list_sink_inputs() -> Result<Vec<SinkInput>> {
+            with_backend(|backend| {
+                if backend != Backend::Pactl {
+                    return Err(Error::NotSupported);
+                }
+                let inputs: Vec<PactlSinkInput> =
+                    serde_json::from_str(&pactl(&["--format=json", "list", "sink-inputs"])?)
+                        .map_err(|error| Error::Parse(error.to_string()))?;
+                Ok(inputs
+                    .into_iter()
+                    .map(PactlSinkInput::into_sink_input)
+                    .collect())
+            })
+        }
+
+        /// Parses `Event 'change' on sink-input #42` of `pactl subscribe`, other facilities
+        /// (sinks, clients...) give `None`
+        fn parse_sink_input_event(line: &str) -> Option<(&str, u32)> {
+            let (kind, rest) = line.strip_prefix("Event '")?.split_once('\'')?;
+            let index = rest.trim().strip_prefix("on sink-input #")?;
+            Some((kind, index.parse().ok()?))
+        }
+        /// Turns the lines of `pactl subscribe` into events, until it exits or the stream
+        /// is dropped. `volumes` holds the last volume of every stream, `change` events that
+        /// don't touch it (mute, pause...) are skipped.
+        fn forward_sink_input_events(
+            subscribe: ChildStdout,
+            mut volumes: HashMap<u32, f64>,
+            events: UnboundedSender<SinkInputEvent>,
+        ) {
+            for line in BufReader::new(subscribe)
+                .lines()
+                .map_while(|line| line.ok())
+            {
+                let (kind, index) = match parse_sink_input_event(&line) {
+                    Some(event) => event,
+                    None => continue,
+                };
+                let event = if kind == "remove" {
+                    volumes.remove(&index);
+                    SinkInputEvent::Removed(index)
+                } else {
+                    // The stream may be gone already, its `remove` follows then
+                    let input = list_sink_inputs()
+                        .ok()
+                        .and_then(|inputs| inputs.into_iter().find(|input| input.index == index));
+                    let input = match input {
+                        Some(input) => input,
+                        None => continue,
+                    };
+                    match volumes.insert(index, input.volume) {
+                        None => SinkInputEvent::Added(input),
+                        Some(volume) if volume != input.volume => {
+                            SinkInputEvent::VolumeChanged(input)
+                        }
+                        Some(_) => continue,
+                    }
+                };
+                if events.unbounded_send(event).is_err() {
+                    break;
+                }
+            }
+        }
+        /// Events of `watch_sink_inputs`, stops `pactl subscribe` when dropped
+        struct SinkInputEvents {
+            events: UnboundedReceiver<SinkInputEvent>,
+            subscribe: Child,
+        }
+        impl Stream for SinkInputEvents {
+            type Item = SinkInputEvent;
+
+            fn poll_next(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<SinkInputEvent>> {
+                self.events.poll_next_unpin(cx)
+            }
+        }
+        impl Drop for SinkInputEvents {
+            fn drop(&mut self) {
+                let _ = self.subscribe.kill();
+                let _ = self.subscribe.wait();
+            }
+        }
+        /// ## Emit the changes of the apps' streams
+        /// Follows `pactl subscribe`, so a mixer stays in sync without polling. Start from
+        /// `list_sink_inputs`, the streams playing already aren't emitted as `Added`.
+        /// `Error::NotSupported` on PipeWire setups without the pulse server. The stream
+        /// ends when the sound server goes away.
+        pub fn watch_sink_inputs() -> Result<impl Stream<Item = SinkInputEvent>> {
+            let volumes = list_sink_inputs()?
+                .into_iter()
+                .map(|input| (input.index, input.volume))
+                .collect();
+            let mut subscribe = Command::new("pactl")
+                .arg("subscribe")
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()?;
+            let stdout = subscribe.stdout.take().ok_or(Error::NotSupported)?;
+            let (sender, events) = mpsc::unbounded();
+            thread::spawn(move || forward_sink_input_events(stdout, volumes, sender));
+            Ok(SinkInputEvents { events, subscribe })
+        }
+
+        /// Volume of the default input (microphone)
+        pub fn get_input_volume() -> Result<f64> {
+            get_volume(Device::Input)
+        }
+        /// Sets the volume of the default input, clamped to 0.0–1.0
+        pub fn set_input_volume(volume: f64) -> Result<()> {
+            set_volume(Device::Input, volume.clamp(0.0, 1.0))
+        }
+        /// Like `set_input_volume` but allows boosting up to `MAX_BOOSTED_VOLUME`,
+        /// which may distort the recorded sound
+        pub fn set_input_volume_with_boost(volume: f64) -> Result<()> {
+            set_volume(Device::Input, volume.clamp(0.0, MAX_BOOSTED_VOLUME))
+        }
+        pub fn is_input_muted() -> Result<bool> {
+            is_muted(Device::Input)
+        }
+        pub fn set_input_muted(muted: bool) -> Result<()> {
+            set_mute(Device::Input, if muted { "1" } else { "0" })
+        }
+        pub fn toggle_input_mute() -> Result<()> {
+            set_mute(Device::Input, "toggle")
+        }
+    }
+
+    /// # Media keys
+    /// Hardware media keys (XF86AudioPlay, XF86AudioNext…) go through gnome-settings-daemon,
+    /// which forwards them to the player that grabbed them most recently. This is how MPRIS
+    /// clients get them on GNOME.
+    /// Volume and mute keys aren't forwarded, the settings daemon handles them itself.
+    pub mod media_keys {
+        use futures_util::{Stream, StreamExt};
+        use zbus::Connection;
+
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::MediaKeysProxy;
+        use crate::watch::watch_signal;
+
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum MediaKey {
+            Play,
+            Pause,
+            Stop,
+            Previous,
+            Next,
+            Rewind,
+            FastForward,
+            Repeat,
+            Shuffle,
+        }
+        impl MediaKey {
+            fn from(key: &str) -> Option<MediaKey> {
+                match key {
+                    "Play" => Some(MediaKey::Play),
+                    "Pause" => Some(MediaKey::Pause),
+                    "Stop" => Some(MediaKey::Stop),
+                    "Previous" => Some(MediaKey::Previous),
+                    "Next" => Some(MediaKey::Next),
+                    "Rewind" => Some(MediaKey::Rewind),
+                    "FastForward" => Some(MediaKey::FastForward),
+                    "Repeat" => Some(MediaKey::Repeat),
+                    "Shuffle" => Some(MediaKey::Shuffle),
+                    _ => None,
+                }
+            }
+        }
+
+        /// ## Receive the media keys
+        /// Grabs the keys for `application` (any name identifying the app, e.g. its
+        /// desktop id) and emits every key pressed while it holds the grab. Another app
+        /// grabbing the keys takes them over until it releases them.
+        /// The stream uses its own connection, dropping it closes the connection, which
+        /// makes the settings daemon release the grab.
+        pub async fn grab(application: &str) -> Result<impl Stream<Item = MediaKey>> {
+            let connection = Connection::session().await?;
+            let proxy = MediaKeysProxy::new(&connection).await?;
+            // Listen before grabbing, a key can be pressed right away
+            let keys = watch_signal::<(String, String)>(&proxy, "MediaPlayerKeyPressed").await?;
+            proxy.GrabMediaPlayerKeys(application, 0).await?;
+            let application = application.to_string();
+            Ok(keys.filter_map(move |(target, key)| {
+                let key = if target == application {
+                    MediaKey::from(&key)
+                } else {
+                    None
+                };
+                async move { key }
+            }))
+        }
+    }
+
+    /// # Airplane mode
+    /// Uses gnome-settings-daemon's rfkill interface, the same one behind the
+    /// airplane mode toggle of GNOME's quick settings, which blocks wifi and bluetooth together.
+    pub mod rfkill {
+        use zbus::Connection;
+
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::{NetworkManagerProxy, RfkillProxy};
+
+        /// Which radios are currently blocked, a UI can use it to show partial states
+        /// (e.g. only bluetooth off)
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub struct RadioState {
+            pub airplane_mode: bool,
+            /// Blocked by a hardware switch, can't be changed from software
+            pub hardware_airplane_mode: bool,
+            pub wifi_blocked: bool,
+            pub bluetooth_blocked: bool,
+        }
+
+        pub async fn get_airplane_mode() -> Result<bool> {
+            let connection = Connection::session().await?;
+            let proxy = RfkillProxy::new(&connection).await?;
+            Ok(proxy.AirplaneMode().await?)
+        }
+        pub async fn set_airplane_mode(enabled: bool) -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = RfkillProxy::new(&connection).await?;
+            Ok(proxy.set_AirplaneMode(enabled).await?)
+        }
+        pub async fn get_radio_state() -> Result<RadioState> {
+            let session = Connection::session().await?;
+            let rfkill = RfkillProxy::new(&session).await?;
+            let system = Connection::system().await?;
+            let network_manager = NetworkManagerProxy::new(&system).await?;
+            Ok(RadioState {
+                airplane_mode: rfkill.AirplaneMode().await?,
+                hardware_airplane_mode: rfkill.HardwareAirplaneMode().await?,
+                wifi_blocked: !network_manager.WirelessEnabled().await?,
+                bluetooth_blocked: rfkill.BluetoothAirplaneMode().await?,
+            })
+        }
+        /// Turns wifi on or off through NetworkManager, like the wifi quick setting.
+        /// Returns `Error::AuthRequired` when polkit refuses.
+        pub async fn set_wifi_enabled(enabled: bool) -> Result<()> {
+            let connection = Connection::system().await?;
+            let proxy = NetworkManagerProxy::new(&connection).await?;
+            Ok(proxy.set_WirelessEnabled(enabled).await?)
+        }
+        /// Turns bluetooth on or off without touching the other radios
+        pub async fn set_bluetooth_enabled(enabled: bool) -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = RfkillProxy::new(&connection).await?;
+            Ok(proxy.set_BluetoothAirplaneMode(!enabled).await?)
+        }
+    }
+
+    pub mod nightlight {
+        use std::sync::mpsc::{self, Sender};
+        use std::sync::Mutex;
+        use std::thread::{self, JoinHandle};
+
+        use futures_util::future::{AbortHandle, Abortable};
+        use futures_util::StreamExt;
+        use gio::glib::DateTime;
+        use zbus::Connection;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::interface::{self, ColorScheme};
+        use crate::handlers::easy_gnome::{ColorProxy, ColorProxyBlocking};
+        use crate::watch::watch_property;
+
+        const MICROSECONDS_PER_DAY: i64 = 86_400_000_000;
+        const COLOR_SCHEMA: &str = "org.gnome.settings-daemon.plugins.color";
+
+        pub fn get_nightlight_active() -> Result<bool> {
+            crate::dconf::get_bool(COLOR_SCHEMA, "night-light-enabled")
+        }
+        pub fn set_nightlight_active(active: bool) -> Result<()> {
+            crate::dconf::set(
+                COLOR_SCHEMA,
+                "night-light-enabled",
+                active.to_string().as_str(),
+            )
+        }
+        /// Flips night light, returns the new value
+        pub fn toggle_nightlight_active() -> Result<bool> {
+            crate::dconf::toggle(COLOR_SCHEMA, "night-light-enabled")
+        }
+        pub fn get_temperature() -> Result<u32> {
+            let value = crate::dconf::get(COLOR_SCHEMA, "night-light-temperature")?;
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::Parse("Unable to read night-light-temperature".to_string()))
+        }
+        pub fn reset_temperature() -> Result<()> {
+            crate::dconf::reset(COLOR_SCHEMA, "night-light-temperature")
+        }
+        /// Only takes effect while night light is active, see `gamma::set_color_temperature`
+        /// to change the screen temperature right away
+        pub fn set_temperature(temperature: u32) -> Result<()> {
+            crate::dconf::set(
+                COLOR_SCHEMA,
+                "night-light-temperature",
+                temperature.to_string().as_str(),
+            )
+        }
+        pub fn get_schedule_automatic() -> Result<bool> {
+            crate::dconf::get_bool(COLOR_SCHEMA, "night-light-schedule-automatic")
+        }
+        pub fn set_schedule_automatic(automatic: bool) -> Result<()> {
+            crate::dconf::set(
+                COLOR_SCHEMA,
+                "night-light-schedule-automatic",
+                automatic.to_string().as_str(),
+            )
+        }
+        pub fn reset_schedule_automatic() -> Result<()> {
+            crate::dconf::reset(COLOR_SCHEMA, "night-light-schedule-automatic")
+        }
+        /// Hour of the day (e.g. `20.5` for 20:30) when the manual schedule starts
+        pub fn get_schedule_from() -> Result<f64> {
+            let value = crate::dconf::get(COLOR_SCHEMA, "night-light-schedule-from")?;
+            value
+                .parse::<f64>()
+                .map_err(|_| Error::Parse("Unable to read night-light-schedule-from".to_string()))
+        }
+        pub fn set_schedule_from(hour: f64) -> Result<()> {
+            crate::dconf::set(
+                COLOR_SCHEMA,
+                "night-light-schedule-from",
+                hour.to_string().as_str(),
+            )
+        }
+        pub fn reset_schedule_from() -> Result<()> {
+            crate::dconf::reset(COLOR_SCHEMA, "night-light-schedule-from")
+        }
+        /// Hour of the day (e.g. `6.0` for 06:00) when the manual schedule ends
+        pub fn get_schedule_to() -> Result<f64> {
+            let value = crate::dconf::get(COLOR_SCHEMA, "night-light-schedule-to")?;
+            value
+                .parse::<f64>()
+                .map_err(|_| Error::Parse("Unable to read night-light-schedule-to".to_string()))
+        }
+        pub fn set_schedule_to(hour: f64) -> Result<()> {
+            crate::dconf::set(
+                COLOR_SCHEMA,
+                "night-light-schedule-to",
+                hour.to_string().as_str(),
+            )
+        }
+        pub fn reset_schedule_to() -> Result<()> {
+            crate::dconf::reset(COLOR_SCHEMA, "night-light-schedule-to")
+        }
+
+        /// Temperature (K) of the screen when night light is off
+        pub const DEFAULT_TEMPERATURE: u32 = 6500;
+        /// Hours the temperature takes to fade in before `from` and to fade out before `to`
+        const SCHEDULE_SMEAR: f64 = 1.0;
+
+        /// A night light schedule, with hours as fractions of the day
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub struct NightLightSchedule {
+            pub from: f64,
+            pub to: f64,
+            pub temperature: u32,
+        }
+        /// Whether `value` falls in `[start, end)`, wrapping around midnight
+        fn frac_day_is_between(value: f64, start: f64, end: f64) -> bool {
+            let end = if end <= start { end + 24.0 } else { end };
+            let value = if value < start && value < end {
+                value + 24.0
+            } else {
+                value
+            };
+            value >= start && value < end
+        }
+        impl NightLightSchedule {
+            /// Temperature active at `hour` (0.0–24.0), following gnome-settings-daemon:
+            /// full temperature between `from` and `to - 1h`, fading linearly from/to
+            /// `DEFAULT_TEMPERATURE` during the hour before `from` and before `to`.
+            pub fn temperature_at(&self, hour: f64) -> u32 {
+                let hour = hour.rem_euclid(24.0);
+                let duration = (self.to - self.from).abs();
+                let smear = SCHEDULE_SMEAR.min(duration.min(24.0 - duration));
+                if !frac_day_is_between(hour, self.from - smear, self.to) {
+                    return DEFAULT_TEMPERATURE;
+                }
+                let day = DEFAULT_TEMPERATURE as f64;
+                let night = self.temperature as f64;
+                if smear < 0.01 {
+                    return self.temperature;
+                }
+                let temperature = if frac_day_is_between(hour, self.from - smear, self.from) {
+                    let factor = 1.0 - ((hour - (self.from - smear)).rem_euclid(24.0) / smear);
+                    (day - night) * factor + night
+                } else if frac_day_is_between(hour, self.to - smear, self.to) {
+                    let factor = (hour - (self.to - smear)).rem_euclid(24.0) / smear;
+                    (day - night) * factor + night
+                } else {
+                    night
+                };
+                temperature.round() as u32
+            }
+        }
+        /// Location the settings daemon saved last, `(91.0, 181.0)` (out of range) until it
+        /// got one
+        fn last_coordinates() -> Option<(f64, f64)> {
+            let value = crate::dconf::get(COLOR_SCHEMA, "night-light-last-coordinates").ok()?;
+            let (lat, lon) = value
+                .trim_matches(|c| c == '(' || c == ')')
+                .split_once(',')?;
+            Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+        }
+        /// Sunset and sunrise of the automatic mode, from the settings daemon or computed
+        /// from its last location when it isn't running
+        fn automatic_hours() -> Option<(f64, f64)> {
+            let from_daemon = || -> Result<(f64, f64)> {
+                let connection = zbus::blocking::Connection::session()?;
+                let proxy = ColorProxyBlocking::new(&connection)?;
+                Ok((proxy.Sunset()?, proxy.Sunrise()?))
+            };
+            let (sunset, sunrise) = match from_daemon() {
+                Ok((sunset, sunrise)) if sunset >= 0.0 && sunrise >= 0.0 => (sunset, sunrise),
+                _ => {
+                    let (lat, lon) = last_coordinates()?;
+                    let (sunrise, sunset) = solar_times(lat, lon, &DateTime::now_local().ok()?)?;
+                    (sunset, sunrise)
+                }
+            };
+            Some((sunset.rem_euclid(24.0), sunrise.rem_euclid(24.0)))
+        }
+        /// The current schedule. In automatic mode `from`/`to` are today's sunset and
+        /// sunrise, as the settings daemon computed them. Like the daemon, the manual hours
+        /// are used while the location is unknown.
+        pub fn get_schedule() -> Result<NightLightSchedule> {
+            let automatic = if get_schedule_automatic()? {
+                automatic_hours()
+            } else {
+                None
+            };
+            let (from, to) = match automatic {
+                Some(hours) => hours,
+                None => (get_schedule_from()?, get_schedule_to()?),
+            };
+            Ok(NightLightSchedule {
+                from,
+                to,
+                temperature: get_temperature()?,
+            })
+        }
+        /// Temperature night light would apply at `hour` (e.g. `21.5` for 21:30)
+        /// with the current settings, `DEFAULT_TEMPERATURE` when night light is off.
+        /// Useful to render a preview curve.
+        pub fn temperature_at(hour: f64) -> Result<u32> {
+            if !get_nightlight_active()? {
+                return Ok(DEFAULT_TEMPERATURE);
+            }
+            Ok(get_schedule()?.temperature_at(hour))
+        }
+        /// ## Sunrise and sunset hours
+        /// Same computation (NOAA's solar calculator) the settings daemon runs in automatic
+        /// mode, for the day of `date` and in its time zone, e.g. `(7.25, 18.5)`.
+        /// Coordinates are in degrees, `None` when they are out of range or the sun doesn't
+        /// rise or set that day (polar day or night).
+        pub fn solar_times(lat: f64, lon: f64, date: &DateTime) -> Option<(f64, f64)> {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                return None;
+            }
+            let dt_zero = DateTime::from_utc(1900, 1, 1, 0, 0, 0.0).ok()?;
+            // Whole days, the time of day doesn't matter
+            let days_since_zero = date.difference(&dt_zero).0 / MICROSECONDS_PER_DAY;
+            let tz_offset = date.utc_offset().0 as f64 / 1e6 / 3600.0;
+
+            let date_as_number = days_since_zero as f64 + 2.0;
+            let julian_day = date_as_number + 2415018.5 - tz_offset / 24.0;
+            let julian_century = (julian_day - 2451545.0) / 36525.0;
+            let geom_mean_long_sun =
+                (280.46646 + julian_century * (36000.76983 + julian_century * 0.0003032)) % 360.0;
+            let geom_mean_anom_sun =
+                357.52911 + julian_century * (35999.05029 - 0.0001537 * julian_century);
+            let eccent_earth_orbit =
+                0.016708634 - julian_century * (0.000042037 + 0.0000001267 * julian_century);
+            let sun_eq_of_ctr = geom_mean_anom_sun.to_radians().sin()
+                * (1.914602 - julian_century * (0.004817 + 0.000014 * julian_century))
+                + (2.0 * geom_mean_anom_sun).to_radians().sin()
+                    * (0.019993 - 0.000101 * julian_century)
+                + (3.0 * geom_mean_anom_sun).to_radians().sin() * 0.000289;
+            let sun_true_long = geom_mean_long_sun + sun_eq_of_ctr;
+            let sun_app_long = sun_true_long
+                - 0.00569
+                - 0.00478 * (125.04 - 1934.136 * julian_century).to_radians().sin();
+            let mean_obliq_ecliptic = 23.0
+                + (26.0
+                    + (21.448
+                        - julian_century
+                            * (46.815 + julian_century * (0.00059 - julian_century * 0.001813)))
+                        / 60.0)
+                    / 60.0;
+            let obliq_corr = mean_obliq_ecliptic
+                + 0.00256 * (125.04 - 1934.136 * julian_century).to_radians().cos();
+            let sun_declin = (obliq_corr.to_radians().sin() * sun_app_long.to_radians().sin())
+                .asin()
+                .to_degrees();
+            let var_y = (obliq_corr / 2.0).to_radians().tan().powi(2);
+            let eq_of_time = 4.0
+                * (var_y * (2.0 * geom_mean_long_sun.to_radians()).sin()
+                    - 2.0 * eccent_earth_orbit * geom_mean_anom_sun.to_radians().sin()
+                    + 4.0
+                        * eccent_earth_orbit
+                        * var_y
+                        * geom_mean_anom_sun.to_radians().sin()
+                        * (2.0 * geom_mean_long_sun.to_radians()).cos()
+                    - 0.5 * var_y * var_y * (4.0 * geom_mean_long_sun.to_radians()).sin()
+                    - 1.25
+                        * eccent_earth_orbit
+                        * eccent_earth_orbit
+                        * (2.0 * geom_mean_anom_sun.to_radians()).sin())
+                .to_degrees();
+            let ha_sunrise = (90.833_f64.to_radians().cos()
+                / (lat.to_radians().cos() * sun_declin.to_radians().cos())
+                - lat.to_radians().tan() * sun_declin.to_radians().tan())
+            .acos()
+            .to_degrees();
+            // Polar circle, no sunrise or sunset
+            if ha_sunrise.is_nan() {
+                return None;
+            }
+            let solar_noon = (720.0 - 4.0 * lon - eq_of_time + tz_offset * 60.0) / 1440.0;
+            let sunrise = solar_noon - ha_sunrise * 4.0 / 1440.0;
+            let sunset = solar_noon + ha_sunrise * 4.0 / 1440.0;
+            Some((sunrise * 24.0, sunset * 24.0))
+        }
+
+        /// Keeps the dark style in sync with night light, see `start_dark_mode_sync`
+        pub struct DarkModeSyncGuard {
+            abort: AbortHandle,
+            thread: Option<JoinHandle<()>>,
+        }
+        impl DarkModeSyncGuard {
+            /// Stops syncing, same as dropping the guard
+            pub fn stop(self) {}
+        }
+        impl Drop for DarkModeSyncGuard {
+            fn drop(&mut self) {
+                self.abort.abort();
+                if let Some(thread) = self.thread.take() {
+                    let _ = thread.join();
+                }
+            }
+        }
+
+        /// Switches to the dark style every time the screen gets warm and back to the scheme
+        /// that was set before once it cools down. `previous` holds that scheme while dark.
+        async fn follow_night_light(previous: &mut Option<ColorScheme>, ready: Sender<Result<()>>) {
+            let watch = async {
+                let connection = Connection::session().await?;
+                let color = ColorProxy::new(&connection).await?;
+                let changes = watch_property::<bool>(&color, "NightLightActive").await;
+                let active = color.NightLightActive().await?;
+                Ok::<_, Error>((changes, active))
+            };
+            let (changes, active) = match watch.await {
+                Ok(watch) => watch,
+                Err(error) => {
+                    let _ = ready.send(Err(error));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            let mut states =
+                Box::pin(futures_util::stream::once(async move { active }).chain(changes));
+            while let Some(active) = states.next().await {
+                match (active, previous.take()) {
+                    (true, None) => {
+                        if let Ok(scheme) = interface::get_color_scheme() {
+                            if interface::set_color_scheme(ColorScheme::PreferDark).is_ok() {
+                                *previous = Some(scheme);
+                            }
+                        }
+                    }
+                    (false, Some(scheme)) => {
+                        let _ = interface::set_color_scheme(scheme);
+                    }
+                    (_, scheme) => *previous = scheme,
+                }
+            }
+        }
+
+        /// ## Dark style at night
+        /// Sets `color-scheme` to prefer-dark while night light warms the screen, following
+        /// the settings daemon's `NightLightActive`, so the theme follows the sunset schedule
+        /// too. The previous scheme comes back when night light turns off or the guard is
+        /// dropped.
+        ///
+        /// Runs on a thread of this process, returns an error when the settings daemon
+        /// can't be reached.
+        pub fn start_dark_mode_sync() -> Result<DarkModeSyncGuard> {
+            let (abort, registration) = AbortHandle::new_pair();
+            let (ready, started) = mpsc::channel::<Result<()>>();
+            let thread = thread::spawn(move || {
+                crate::runtime::block_on(async {
+                    let mut previous = None;
+                    let follow = follow_night_light(&mut previous, ready);
+                    let _ = Abortable::new(follow, registration).await;
+                    if let Some(scheme) = previous {
+                        let _ = interface::set_color_scheme(scheme);
+                    }
+                })
+            });
+            let guard = DarkModeSyncGuard {
+                abort,
+                thread: Some(thread),
+            };
+            started.recv().unwrap_or(Err(Error::Cancelled))?;
+            Ok(guard)
+        }
+
+        static DARK_MODE_SYNC: Mutex<Option<DarkModeSyncGuard>> = Mutex::new(None);
+
+        /// Turns the night light and dark style sync on or off for the whole process
+        pub fn sync_with_dark_mode(enabled: bool) -> Result<()> {
+            let mut dark_mode_sync = DARK_MODE_SYNC.lock().unwrap();
+            if !enabled {
+                *dark_mode_sync = None;
+            } else if dark_mode_sync.is_none() {
+                *dark_mode_sync = Some(start_dark_mode_sync()?);
+            }
+            Ok(())
+        }
+        pub fn is_synced_with_dark_mode() -> bool {
+            DARK_MODE_SYNC.lock().unwrap().is_some()
+        }
+    }
+
+    /// # Location
+    /// Current position from GeoClue, the service GNOME uses for automatic night light
+    /// and time zone.
+    pub mod location {
+        use futures_util::StreamExt;
+        use zbus::Connection;
+        use zvariant::OwnedObjectPath;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::{
+            GeoClueClientProxy, GeoClueLocationProxy, GeoClueManagerProxy,
+        };
+        use crate::watch::watch_signal;
+
+        /// Desktop id `get_location` identifies itself with
+        pub const DESKTOP_ID: &str = "gnome-dbus-api";
+        /// `GCLUE_ACCURACY_LEVEL_EXACT`
+        const ACCURACY_LEVEL_EXACT: u32 = 8;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct Location {
+            pub latitude: f64,
+            pub longitude: f64,
+            /// Radius in meters
+            pub accuracy: f64,
+            /// Meters above sea level, `None` when unknown
+            pub altitude: Option<f64>,
+        }
+
+        /// ## Current location
+        /// Waits for the first fix GeoClue reports.
+        /// Returns `Error::LocationDenied` when location services are off in Settings or the
+        /// user refused access.
+        pub async fn get_location() -> Result<Location> {
+            get_location_for(DESKTOP_ID).await
+        }
+        /// Same as `get_location` for the app with the given desktop id (without `.desktop`),
+        /// which is what the permission is granted to
+        pub async fn get_location_for(desktop_id: &str) -> Result<Location> {
+            let connection = Connection::system().await?;
+            let manager = GeoClueManagerProxy::new(&connection).await?;
+            let client = GeoClueClientProxy::builder(&connection)
+                .path(manager.GetClient().await?)?
+                .build()
+                .await?;
+            client.set_DesktopId(desktop_id).await?;
+            client
+                .set_RequestedAccuracyLevel(ACCURACY_LEVEL_EXACT)
+                .await?;
+
+            // Listen before starting, the first fix can come right away
+            let updates =
+                watch_signal::<(OwnedObjectPath, OwnedObjectPath)>(&client, "LocationUpdated")
+                    .await?;
+            futures_util::pin_mut!(updates);
+            client
+                .Start()
+                .await
+                .map_err(|error| match Error::from(error) {
+                    Error::AuthRequired => Error::LocationDenied,
+                    error => error,
+                })?;
+            let (_, new_location) = updates.next().await.ok_or_else(|| {
+                zbus::Error::Failure("GeoClue stopped without a location".to_string())
+            })?;
+            let location = GeoClueLocationProxy::builder(&connection)
+                .path(new_location)?
+                .build()
+                .await?;
+            // GeoClue uses -DBL_MAX for an unknown altitude
+            let altitude = location.Altitude().await?;
+            let result = Location {
+                latitude: location.Latitude().await?,
+                longitude: location.Longitude().await?,
+                accuracy: location.Accuracy().await?,
+                altitude: (altitude > f64::MIN).then_some(altitude),
+            };
+            client.Stop().await?;
+            Ok(result)
+        }
+    }
+
+    /// # Monitors
+    /// Layout of the connected monitors as Mutter sees it.
+    pub mod display {
+        use futures_util::{FutureExt, Stream, StreamExt};
+        use zbus::{Connection, SignalStream};
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::{get_value, DisplayConfigProxy, SensorProxyProxy};
+        use crate::watch::watch_property;
+
+        const TOUCHSCREEN_SCHEMA: &str = "org.gnome.settings-daemon.peripherals.touchscreen";
+
+        /// `layout-mode` of the current state, sizes are scaled down in logical mode
+        const LOGICAL_LAYOUT_MODE: u32 = 1;
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Monitor {
+            /// Connector name, e.g. `eDP-1` or `HDMI-1`
+            pub connector: String,
+            /// Name shown in Settings, e.g. `Built-in display`
+            pub display_name: String,
+            /// EDID vendor, product and serial, which identify the monitor whatever port
+            /// it's plugged in
+            pub vendor: String,
+            pub product: String,
+            pub serial: String,
+            /// Position and size in the logical coordinates used by the shell
+            pub x: i32,
+            pub y: i32,
+            pub width: i32,
+            pub height: i32,
+            pub scale: f64,
+            pub primary: bool,
+        }
+
+        /// ## Enabled monitors, in Mutter's order
+        /// Mirrored monitors share the same position and size.
+        /// Every call asks Mutter for its whole state, use a `DisplayState` to query often.
+        pub async fn list_monitors() -> Result<Vec<Monitor>> {
+            let connection = Connection::session().await?;
+            let proxy = DisplayConfigProxy::new(&connection).await?;
+            current_monitors(&proxy).await
+        }
+        async fn current_monitors(proxy: &DisplayConfigProxy<'_>) -> Result<Vec<Monitor>> {
+            let (_, monitors, logical_monitors, properties) = proxy.GetCurrentState().await?;
+            let layout_mode: u32 =
+                get_value(&properties, "layout-mode").unwrap_or(LOGICAL_LAYOUT_MODE);
+
+            let mut list: Vec<Monitor> = Vec::new();
+            for (x, y, scale, transform, primary, specs, _) in logical_monitors {
+                for spec in specs {
+                    let (_, modes, monitor_properties) = match monitors
+                        .iter()
+                        .find(|(monitor_spec, _, _)| *monitor_spec == spec)
+                    {
+                        Some(monitor) => monitor,
+                        None => continue,
+                    };
+                    let current_mode = modes.iter().find(|(.., mode_properties)| {
+                        get_value::<bool>(mode_properties, "is-current").unwrap_or(false)
+                    });
+                    let (_, mode_width, mode_height, ..) = match current_mode {
+                        Some(mode) => mode,
+                        None => continue,
+                    };
+                    let (mut width, mut height) = (*mode_width, *mode_height);
+                    // Odd transforms are rotated by 90 or 270 degrees
+                    if transform % 2 == 1 {
+                        std::mem::swap(&mut width, &mut height);
+                    }
+                    if layout_mode == LOGICAL_LAYOUT_MODE {
+                        width = (width as f64 / scale).round() as i32;
+                        height = (height as f64 / scale).round() as i32;
+                    }
+                    list.push(Monitor {
+                        connector: spec.0.clone(),
+                        display_name: get_value(monitor_properties, "display-name")
+                            .unwrap_or_else(|_| spec.0.clone()),
+                        vendor: spec.1.clone(),
+                        product: spec.2.clone(),
+                        serial: spec.3.clone(),
+                        x,
+                        y,
+                        width,
+                        height,
+                        scale,
+                        primary,
+                    });
+                }
+            }
+            Ok(list)
+        }
+
+        /// ## Cached monitor list
+        /// Fetches the monitors once and keeps them until Mutter emits `MonitorsChanged`
+        /// (hotplug, new mode or layout), so display settings UIs can call `list_monitors`
+        /// as often as they like.
+        pub struct DisplayState {
+            proxy: DisplayConfigProxy<'static>,
+            changes: SignalStream<'static>,
+            monitors: Option<Vec<Monitor>>,
+        }
+        impl DisplayState {
+            pub async fn new() -> Result<DisplayState> {
+                let connection = Connection::session().await?;
+                let proxy = DisplayConfigProxy::new(&connection).await?;
+                let changes = proxy.receive_signal("MonitorsChanged").await?;
+                Ok(DisplayState {
+                    proxy,
+                    changes,
+                    monitors: None,
+                })
+            }
+            /// Same as `display::list_monitors`, only asks Mutter again after a change
+            pub async fn list_monitors(&mut self) -> Result<Vec<Monitor>> {
+                // Signals received since the last call, without waiting for new ones
+                while let Some(Some(_)) = self.changes.next().now_or_never() {
+                    self.monitors = None;
+                }
+                if let Some(monitors) = &self.monitors {
+                    return Ok(monitors.clone());
+                }
+                let monitors = current_monitors(&self.proxy).await?;
+                self.monitors = Some(monitors.clone());
+                Ok(monitors)
+            }
+            /// Drops the cached list, the next `list_monitors` asks Mutter again
+            pub fn invalidate(&mut self) {
+                self.monitors = None;
+            }
+        }
+
+        /// Emits the fresh monitor list every time Mutter's configuration changes,
+        /// e.g. a monitor is plugged in or the layout is applied
+        pub async fn watch_monitors() -> Result<impl Stream<Item = Vec<Monitor>>> {
+            let connection = Connection::session().await?;
+            let proxy = DisplayConfigProxy::new(&connection).await?;
+            let changes = proxy.receive_signal("MonitorsChanged").await?;
+            Ok(changes.filter_map(move |_| {
+                let proxy = proxy.clone();
+                async move { current_monitors(&proxy).await.ok() }
+            }))
+        }
+
+        /// Which edge of the device points up, as reported by the accelerometer
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum Orientation {
+            Normal,
+            BottomUp,
+            LeftUp,
+            RightUp,
+            /// Lying flat or not known yet
+            Undefined,
+        }
+        impl Orientation {
+            fn from(orientation: &str) -> Orientation {
+                match orientation {
+                    "normal" => Orientation::Normal,
+                    "bottom-up" => Orientation::BottomUp,
+                    "left-up" => Orientation::LeftUp,
+                    "right-up" => Orientation::RightUp,
+                    _ => Orientation::Undefined,
+                }
+            }
+        }
+
+        /// Whether iio-sensor-proxy found an accelerometer, `false` when it isn't running
+        pub async fn has_accelerometer() -> bool {
+            let has_accelerometer = async {
+                let connection = Connection::system().await?;
+                let proxy = SensorProxyProxy::new(&connection).await?;
+                proxy.HasAccelerometer().await
+            };
+            match has_accelerometer.await {
+                Ok(has_accelerometer) => has_accelerometer,
+                Err(_) => false,
+            }
+        }
+        /// ## Emit the device orientation every time it changes
+        /// Claims the accelerometer from iio-sensor-proxy for as long as the stream lives,
+        /// the stream uses its own connection and the claim is dropped with it.
+        /// Combined with `set_orientation_lock(true)` an app can rotate on its own terms.
+        /// Returns `Error::NotSupported` without an accelerometer.
+        pub async fn watch_orientation() -> Result<impl Stream<Item = Orientation>> {
+            let connection = Connection::system().await?;
+            let proxy = SensorProxyProxy::new(&connection).await?;
+            if !proxy.HasAccelerometer().await.unwrap_or(false) {
+                return Err(Error::NotSupported);
+            }
+            proxy.ClaimAccelerometer().await?;
+            let orientations = watch_property::<String>(&proxy, "AccelerometerOrientation").await;
+            Ok(orientations.map(|orientation| Orientation::from(&orientation)))
+        }
+
+        /// ## Orientation lock
+        /// Stops GNOME from rotating the screen with the device, like the quick settings
+        /// toggle. Returns `Error::NotSupported` without an accelerometer, where GNOME never
+        /// rotates anyway.
+        pub async fn set_orientation_lock(locked: bool) -> Result<()> {
+            if !has_accelerometer().await {
+                return Err(Error::NotSupported);
+            }
+            crate::dconf::set(
+                TOUCHSCREEN_SCHEMA,
+                "orientation-lock",
+                locked.to_string().as_str(),
+            )
+        }
+        pub async fn get_orientation_lock() -> Result<bool> {
+            if !has_accelerometer().await {
+                return Err(Error::NotSupported);
+            }
+            crate::dconf::get_bool(TOUCHSCREEN_SCHEMA, "orientation-lock")
+        }
+    }
+
+    /// # Manual screen color temperature
+    /// Like redshift, writes the gamma ramps of every active CRTC through Mutter.
+    /// Unlike `nightlight::set_temperature`, which stores the temperature night light uses
+    /// while it's active, this applies right away, whatever the night light schedule.
+    /// The ramps are replaced again when the settings daemon updates them (night light
+    /// turning on or off, a color profile change) or when the monitors are reconfigured.
+    pub mod gamma {
+        use zbus::Connection;
+
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::nightlight::DEFAULT_TEMPERATURE;
+        use crate::handlers::easy_gnome::DisplayConfigProxy;
+
+        pub const MIN_TEMPERATURE: u32 = 1000;
+        pub const MAX_TEMPERATURE: u32 = 10000;
+
+        /// Blackbody color of a temperature, channels from 0 to 1
+        fn blackbody(temperature: u32) -> (f64, f64, f64) {
+            // Tanner Helland's fit of the blackbody curve
+            let t = temperature as f64 / 100.0;
+            let red = if t <= 66.0 {
+                255.0
+            } else {
+                329.698727446 * (t - 60.0).powf(-0.1332047592)
+            };
+            let green = if t <= 66.0 {
+                99.4708025861 * t.ln() - 161.1195681661
+            } else {
+                288.1221695283 * (t - 60.0).powf(-0.0755148492)
+            };
+            let blue = if t >= 66.0 {
+                255.0
+            } else if t <= 19.0 {
+                0.0
+            } else {
+                138.5177312231 * (t - 10.0).ln() - 305.0447927307
+            };
+            let channel = |value: f64| value.clamp(0.0, 255.0) / 255.0;
+            (channel(red), channel(green), channel(blue))
+        }
+        /// Channel multipliers, `DEFAULT_TEMPERATURE` being the identity
+        fn temperature_to_rgb(temperature: u32) -> (f64, f64, f64) {
+            let temperature = temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+            let (red, green, blue) = blackbody(temperature);
+            let (white_red, white_green, white_blue) = blackbody(DEFAULT_TEMPERATURE);
+            (
+                (red / white_red).min(1.0),
+                (green / white_green).min(1.0),
+                (blue / white_blue).min(1.0),
+            )
+        }
+        fn ramp(size: usize, factor: f64) -> Vec<u16> {
+            let last = size.saturating_sub(1).max(1) as f64;
+            (0..size)
+                .map(|i| (i as f64 / last * factor * u16::MAX as f64).round() as u16)
+                .collect()
+        }
+
+        /// Set the color temperature in Kelvin, clamped between `MIN_TEMPERATURE` and
+        /// `MAX_TEMPERATURE`. Lower is warmer, `DEFAULT_TEMPERATURE` is neutral.
+        pub async fn set_color_temperature(temperature: u32) -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = DisplayConfigProxy::new(&connection).await?;
+            let (serial, crtcs, ..) = proxy.GetResources().await?;
+            let (red, green, blue) = temperature_to_rgb(temperature);
+            for (crtc, _, _, _, _, _, current_mode, ..) in crtcs {
+                // Disabled CRTCs have no mode
+                if current_mode < 0 {
+                    continue;
+                }
+                let (current_red, ..) = proxy.GetCrtcGamma(serial, crtc).await?;
+                let size = current_red.len();
+                proxy
+                    .SetCrtcGamma(
+                        serial,
+                        crtc,
+                        ramp(size, red),
+                        ramp(size, green),
+                        ramp(size, blue),
+                    )
+                    .await?;
+            }
+            Ok(())
+        }
+        /// Back to neutral (linear) gamma ramps
+        pub async fn reset() -> Result<()> {
+            set_color_temperature(DEFAULT_TEMPERATURE).await
+        }
+    }
+
+    /// # Color management
+    /// ICC profiles and the devices they apply to, from colord. GNOME's settings daemon
+    /// registers every monitor (kind `display`) and applies its default profile.
+    pub mod color_management {
+        use zbus::Connection;
+        use zvariant::OwnedObjectPath;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::{
+            ColorManagerDeviceProxy, ColorManagerProfileProxy, ColorManagerProxy,
+        };
+
+        const NOT_FOUND_ERROR_NAME: &str = "org.freedesktop.ColorManager.NotFound";
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct ColorDevice {
+            /// colord id, e.g. `xrandr-Dell Inc.-DELL U2415-7MT0167B2YNL`
+            pub id: String,
+            /// `display`, `printer`, `scanner`, `camera` or `webcam`
+            pub kind: String,
+            pub vendor: String,
+            pub model: String,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct ColorProfile {
+            /// colord id, e.g. `icc-9a5e1c3fb4a3e1b4bd2f10f3b4c3a7d9`
+            pub id: String,
+            pub title: String,
+            /// Path of the ICC file, empty for virtual profiles
+            pub filename: String,
+            /// `display-device`, `output-device`, `input-device`...
+            pub kind: String,
+            /// `rgb`, `cmyk`, `gray`...
+            pub colorspace: String,
+        }
+
+        /// Maps colord's `NotFound` to `Error::InvalidArgument`
+        fn not_found(what: &str, id: &str) -> impl Fn(zbus::Error) -> Error {
+            let message = format!("No color {} with id {}", what, id);
+            move |error| match error {
+                zbus::Error::MethodError(ref name, _, _)
+                    if name.as_str() == NOT_FOUND_ERROR_NAME =>
+                {
+                    Error::InvalidArgument(message.clone())
+                }
+                error => Error::from(error),
+            }
+        }
+
+        async fn device_proxy(
+            connection: &Connection,
+            device_id: &str,
+        ) -> Result<ColorManagerDeviceProxy<'static>> {
+            let manager = ColorManagerProxy::new(connection).await?;
+            let path = manager
+                .FindDeviceById(device_id)
+                .await
+                .map_err(not_found("device", device_id))?;
+            Ok(ColorManagerDeviceProxy::builder(connection)
+                .path(path)?
+                .build()
+                .await?)
+        }
+        async fn read_profile(
+            connection: &Connection,
+            path: OwnedObjectPath,
+        ) -> Result<ColorProfile> {
+            let profile = ColorManagerProfileProxy::builder(connection)
+                .path(path)?
+                .build()
+                .await?;
+            Ok(ColorProfile {
+                id: profile.ProfileId().await?,
+                title: profile.Title().await?,
+                filename: profile.Filename().await?,
+                kind: profile.Kind().await?,
+                colorspace: profile.Colorspace().await?,
+            })
+        }
+
+        /// Devices known to colord, monitors first show up once GNOME registered them
+        pub async fn list_devices() -> Result<Vec<ColorDevice>> {
+            let connection = Connection::system().await?;
+            let manager = ColorManagerProxy::new(&connection).await?;
+            let mut devices = Vec::new();
+            for path in manager.GetDevices().await? {
+                let device = ColorManagerDeviceProxy::builder(&connection)
+                    .path(path)?
+                    .build()
+                    .await?;
+                devices.push(ColorDevice {
+                    id: device.DeviceId().await?,
+                    kind: device.Kind().await?,
+                    vendor: device.Vendor().await?,
+                    model: device.Model().await?,
+                });
+            }
+            Ok(devices)
+        }
+        /// Every installed profile, system wide and the user's (`~/.local/share/icc`)
+        pub async fn list_profiles() -> Result<Vec<ColorProfile>> {
+            let connection = Connection::system().await?;
+            let manager = ColorManagerProxy::new(&connection).await?;
+            let mut profiles = Vec::new();
+            for path in manager.GetProfiles().await? {
+                profiles.push(read_profile(&connection, path).await?);
+            }
+            Ok(profiles)
+        }
+        /// Profiles that can be assigned to the device
+        pub async fn list_device_profiles(device_id: &str) -> Result<Vec<ColorProfile>> {
+            let connection = Connection::system().await?;
+            let device = device_proxy(&connection, device_id).await?;
+            let mut profiles = Vec::new();
+            for path in device.Profiles().await? {
+                profiles.push(read_profile(&connection, path).await?);
+            }
+            Ok(profiles)
+        }
+        /// The default profile of a device, `None` when it has none (uncalibrated).
+        /// Returns `Error::InvalidArgument` for an unknown device.
+        pub async fn get_active_profile(device_id: &str) -> Result<Option<ColorProfile>> {
+            let connection = Connection::system().await?;
+            let device = device_proxy(&connection, device_id).await?;
+            match device.Profiles().await?.into_iter().next() {
+                Some(path) => Ok(Some(read_profile(&connection, path).await?)),
+                None => Ok(None),
+            }
+        }
+        /// ## Make a profile the default of a device
+        /// Like picking it in Settings > Color. GNOME applies it to the monitor right away.
+        /// Returns `Error::InvalidArgument` for an unknown device or profile and
+        /// `Error::AuthRequired` when polkit refuses.
+        pub async fn set_active_profile(device_id: &str, profile_id: &str) -> Result<()> {
+            let connection = Connection::system().await?;
+            let device = device_proxy(&connection, device_id).await?;
+            let manager = ColorManagerProxy::new(&connection).await?;
+            let profile = manager
+                .FindProfileById(profile_id)
+                .await
+                .map_err(not_found("profile", profile_id))?;
+            Ok(device.MakeProfileDefault(&profile).await?)
+        }
+    }
+
+    /// # Image encoding
+    /// Shared by app icons and screenshots.
+    pub mod image_utils {
+        use std::io::Cursor;
+
+        use image::imageops::{self, FilterType};
+        use image::{ImageOutputFormat, ImageResult, Rgb, RgbImage, RgbaImage};
+
+        use crate::error::{Error, Result};
+
+        const JPEG_QUALITY: u8 = 90;
+
+        #[derive(PartialEq, Debug, Clone, Copy, Default)]
+        pub enum ImageFormat {
+            #[default]
+            Png,
+            /// Lossy WebP, much smaller than PNG for icons
+            WebP,
+            /// No transparency, transparent pixels are blended over white
+            Jpeg,
+        }
+        impl ImageFormat {
+            pub fn mime_type(&self) -> &'static str {
+                match self {
+                    ImageFormat::Png => "image/png",
+                    ImageFormat::WebP => "image/webp",
+                    ImageFormat::Jpeg => "image/jpeg",
+                }
+            }
+        }
+
+        fn blend_over_white(image: &RgbaImage) -> RgbImage {
+            RgbImage::from_fn(image.width(), image.height(), |x, y| {
+                let [red, green, blue, alpha] = image.get_pixel(x, y).0;
+                let blend = |channel: u8| {
+                    let alpha = alpha as u32;
+                    ((channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8
+                };
+                Rgb([blend(red), blend(green), blend(blue)])
+            })
+        }
+        pub fn encode(image: &RgbaImage, format: ImageFormat) -> ImageResult<Vec<u8>> {
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut writer = Cursor::new(&mut bytes);
+            match format {
+                ImageFormat::Png => image.write_to(&mut writer, ImageOutputFormat::Png)?,
+                ImageFormat::WebP => image.write_to(&mut writer, ImageOutputFormat::WebP)?,
+                ImageFormat::Jpeg => blend_over_white(image)
+                    .write_to(&mut writer, ImageOutputFormat::Jpeg(JPEG_QUALITY))?,
+            }
+            Ok(bytes)
+        }
+        /// Decodes an image in any format the `image` crate knows and encodes it as `format`
+        pub fn convert(bytes: &[u8], format: ImageFormat) -> ImageResult<Vec<u8>> {
+            encode(&image::load_from_memory(bytes)?.to_rgba8(), format)
+        }
+        /// Shrinks `image` so neither side exceeds `max_dim`, keeping the aspect ratio.
+        /// Smaller images are returned as they are, never upscaled.
+        pub fn scale_down(image: &RgbaImage, max_dim: u32) -> RgbaImage {
+            let (width, height) = image.dimensions();
+            if width <= max_dim && height <= max_dim {
+                return image.clone();
+            }
+            let scale = max_dim as f64 / width.max(height) as f64;
+            let new_width = ((width as f64 * scale).round() as u32).max(1);
+            let new_height = ((height as f64 * scale).round() as u32).max(1);
+            imageops::resize(image, new_width, new_height, FilterType::Lanczos3)
+        }
+        /// ## Small PNG preview of an image
+        /// `bytes` can be in any format the `image` crate decodes, the result fits in a
+        /// `max_dim` square. Bytes that aren't an image return `Error::Parse`.
+        pub fn thumbnail(bytes: &[u8], max_dim: u32) -> Result<Vec<u8>> {
+            let image =
+                image::load_from_memory(bytes).map_err(|error| Error::Parse(error.to_string()))?;
+            encode(&scale_down(&image.to_rgba8(), max_dim), ImageFormat::Png)
+                .map_err(|error| Error::Parse(error.to_string()))
+        }
+    }
+
+    /// # Clipboard
+    /// Reads and writes the clipboard through GTK, which must be initialized. Call these
+    /// from the thread GTK runs on.
+    /// What is set stays available while the process runs, the clipboard manager keeps a
+    /// copy after it exits when there is one.
+    pub mod clipboard {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::VecDeque;
+        use std::hash::{Hash, Hasher};
+        use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+        use gio::prelude::*;
+        use gtk::gdk_pixbuf::Pixbuf;
+        use gtk::Clipboard;
+
+        use crate::error::{Error, Result};
+
+        fn clipboard() -> Clipboard {
+            Clipboard::get(&gdk::SELECTION_CLIPBOARD)
+        }
+
+        pub fn get_text() -> Option<String> {
+            clipboard().wait_for_text().map(|text| text.to_string())
+        }
+        pub fn set_text(text: &str) {
+            let clipboard = clipboard();
+            clipboard.set_text(text);
+            clipboard.store();
+        }
+        fn primary() -> Clipboard {
+            Clipboard::get(&gdk::SELECTION_PRIMARY)
+        }
+        /// ## Primary selection
+        /// The text last selected with the mouse, pasted with a middle click. It's separate
+        /// from the clipboard and not kept by clipboard managers, so what is set is only
+        /// available while the process runs.
+        /// Wayland compositors without the primary selection protocol don't have it, the
+        /// getter then returns `None` and the setter does nothing.
+        pub fn get_primary_text() -> Option<String> {
+            primary().wait_for_text().map(|text| text.to_string())
+        }
+        /// See `get_primary_text`
+        pub fn set_primary_text(text: &str) {
+            primary().set_text(text);
+        }
+        /// Image on the clipboard as PNG bytes, whatever format the owner offers
+        pub fn get_image() -> Result<Option<Vec<u8>>> {
+            match clipboard().wait_for_image() {
+                Some(pixbuf) => Ok(Some(pixbuf.save_to_bufferv("png", &[])?)),
+                None => Ok(None),
+            }
+        }
+        /// Puts an image on the clipboard, `bytes` can be in any format GdkPixbuf decodes
+        pub fn set_image(bytes: &[u8]) -> Result<()> {
+            let stream = gio::MemoryInputStream::from_bytes(&gio::glib::Bytes::from(bytes));
+            let pixbuf = Pixbuf::from_stream(&stream, None::<&gio::Cancellable>)?;
+            let clipboard = clipboard();
+            clipboard.set_image(&pixbuf);
+            clipboard.store();
+            Ok(())
+        }
+        /// MIME types (targets) the clipboard owner offers, e.g. `image/png` or
+        /// `text/plain;charset=utf-8`. Empty when the clipboard is empty.
+        pub fn available_types() -> Vec<String> {
+            clipboard()
+                .wait_for_targets()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|target| target.name().to_string())
+                .collect()
+        }
+        /// Image types from the most to the least preferred, lossless first
+        const IMAGE_MIME_TYPES: [&str; 6] = [
+            "image/png",
+            "image/webp",
+            "image/jpeg",
+            "image/gif",
+            "image/bmp",
+            "image/tiff",
+        ];
+        /// ## Image on the clipboard as the owner encoded it
+        /// Unlike `get_image` the bytes aren't converted, the MIME type tells their format.
+        /// Picks the most preferred type of `IMAGE_MIME_TYPES` that is offered, or else the
+        /// first `image/` type.
+        pub fn get_best_image() -> Option<(Vec<u8>, String)> {
+            best_image(available_types())
+        }
+        fn best_image(types: Vec<String>) -> Option<(Vec<u8>, String)> {
+            let mime_type = IMAGE_MIME_TYPES
+                .iter()
+                .find(|mime_type| types.iter().any(|t| t == *mime_type))
+                .map(|mime_type| mime_type.to_string())
+                .or_else(|| types.into_iter().find(|t| t.starts_with("image/")))?;
+            let data = clipboard()
+                .wait_for_contents(&gdk::Atom::intern(&mime_type))?
+                .data();
+            if data.is_empty() {
+                return None;
+            }
+            Some((data, mime_type))
+        }
+        /// File extension for an image MIME type, `bin` when unknown
+        pub fn extension_for(mime_type: &str) -> &'static str {
+            match mime_type {
+                "image/png" => "png",
+                "image/webp" => "webp",
+                "image/jpeg" => "jpg",
+                "image/gif" => "gif",
+                "image/bmp" => "bmp",
+                "image/tiff" => "tiff",
+                _ => "bin",
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Hash)]
+        pub enum ClipboardContent {
+            Text(String),
+            /// Image as offered by the clipboard owner, `mime_type` tells the format
+            Image {
+                data: Vec<u8>,
+                mime_type: String,
+            },
+        }
+        impl ClipboardContent {
+            /// Extension to save the content with, `txt` for text
+            pub fn extension(&self) -> &'static str {
+                match self {
+                    ClipboardContent::Text(_) => "txt",
+                    ClipboardContent::Image { mime_type, .. } => extension_for(mime_type),
+                }
+            }
+        }
+        /// What the clipboard currently holds, an image or else text
+        pub fn get_content() -> Option<ClipboardContent> {
+            get_snapshot().content()
+        }
+
+        /// Targets GTK converts to text, besides the `text/plain` ones
+        const TEXT_TARGETS: [&str; 3] = ["UTF8_STRING", "STRING", "TEXT"];
+
+        /// ## Everything the clipboard offers at once
+        /// Copying rich content (e.g. from a browser) offers text, HTML and an image
+        /// together, plain text copies only have `text`.
+        #[derive(Debug, Clone, PartialEq, Hash, Default)]
+        pub struct ClipboardSnapshot {
+            /// Every MIME type offered, see `available_types`
+            pub types: Vec<String>,
+            pub text: Option<String>,
+            pub html: Option<String>,
+            /// The preferred image as offered, with its MIME type, see `get_best_image`
+            pub image: Option<(Vec<u8>, String)>,
+        }
+        impl ClipboardSnapshot {
+            pub fn is_empty(&self) -> bool {
+                self.text.is_none() && self.html.is_none() && self.image.is_none()
+            }
+            /// The content a history keeps: the image if there's one, or else the text
+            pub fn content(self) -> Option<ClipboardContent> {
+                match self.image {
+                    Some((data, mime_type)) => Some(ClipboardContent::Image { data, mime_type }),
+                    None => self.text.map(ClipboardContent::Text),
+                }
+            }
+        }
+        /// Reads every type the clipboard offers, only types that are offered are fetched
+        pub fn get_snapshot() -> ClipboardSnapshot {
+            let types = available_types();
+            let has_text = types
+                .iter()
+                .any(|t| t.starts_with("text/plain") || TEXT_TARGETS.contains(&t.as_str()));
+            let text = if has_text { get_text() } else { None };
+            let html = if types.iter().any(|t| t == "text/html") {
+                clipboard()
+                    .wait_for_contents(&gdk::Atom::intern("text/html"))
+                    .map(|data| String::from_utf8_lossy(&data.data()).into_owned())
+                    .filter(|html| !html.is_empty())
+            } else {
+                None
+            };
+            let image = best_image(types.clone());
+            ClipboardSnapshot {
+                types,
+                text,
+                html,
+                image,
+            }
+        }
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct HistoryEntry {
+            pub content: ClipboardContent,
+            /// When the entry was recorded, in seconds since the Unix epoch
+            pub timestamp: i64,
+        }
+
+        pub const DEFAULT_HISTORY_SIZE: usize = 50;
+
+        /// ## Clipboard history
+        /// Keeps the last clipboard contents in memory, newest first. Nothing is recorded on
+        /// its own, call `record` when the clipboard changes, e.g. from GTK's `owner-change`
+        /// signal.
+        #[derive(Debug, Clone)]
+        pub struct History {
+            entries: VecDeque<HistoryEntry>,
+            max_size: usize,
+        }
+        impl History {
+            /// Keeps at most `max_size` entries, the oldest are dropped first
+            pub fn new(max_size: usize) -> History {
+                History {
+                    entries: VecDeque::new(),
+                    max_size,
+                }
+            }
+            /// Entries from the newest to the oldest
+            pub fn entries(&self) -> &VecDeque<HistoryEntry> {
+                &self.entries
+            }
+            pub fn clear(&mut self) {
+                self.entries.clear();
+            }
+            pub fn max_size(&self) -> usize {
+                self.max_size
+            }
+            /// Drops the oldest entries that don't fit anymore
+            pub fn set_max_size(&mut self, max_size: usize) {
+                self.max_size = max_size;
+                self.entries.truncate(max_size);
+            }
+            /// Adds `content` as the newest entry, unless it's already the newest one.
+            /// Returns whether it was added.
+            pub fn push(&mut self, content: ClipboardContent) -> bool {
+                if self.max_size == 0
+                    || self.entries.front().map(|entry| &entry.content) == Some(&content)
+                {
+                    return false;
+                }
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs() as i64)
+                    .unwrap_or(0);
+                self.entries.push_front(HistoryEntry { content, timestamp });
+                self.entries.truncate(self.max_size);
+                true
+            }
+            /// Records what the clipboard currently holds, an image or else text.
+            /// Returns whether an entry was added.
+            pub fn record(&mut self) -> bool {
+                match get_content() {
+                    Some(content) => self.push(content),
+                    None => false,
+                }
+            }
+            /// Puts the entry at `index` back on the clipboard, it becomes the newest entry
+            pub fn restore(&mut self, index: usize) -> Result<()> {
+                let content = match self.entries.get(index) {
+                    Some(entry) => entry.content.clone(),
+                    None => {
+                        return Err(Error::InvalidArgument(format!(
+                            "No history entry at index {}",
+                            index
+                        )))
+                    }
+                };
+                match &content {
+                    ClipboardContent::Text(text) => set_text(text),
+                    ClipboardContent::Image { data, .. } => set_image(data)?,
+                }
+                self.entries.remove(index);
+                self.push(content);
+                Ok(())
+            }
+        }
+        impl Default for History {
+            fn default() -> Self {
+                History::new(DEFAULT_HISTORY_SIZE)
+            }
+        }
+
+        /// Fast enough to feel instant, slow enough not to keep the clipboard owner busy
+        pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum ClipboardEvent {
+            /// Holds every type offered, store `snapshot.content()` to keep one of them
+            Changed(ClipboardSnapshot),
+            /// The clipboard was emptied, e.g. its owner quit without a clipboard manager
+            Cleared,
+        }
+
+        fn snapshot_hash(snapshot: &ClipboardSnapshot) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            snapshot.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        /// ## Poll the clipboard for changes
+        /// For consumers without a GTK main loop to receive `owner-change`. Only a hash of
+        /// the last content is kept, so large images aren't compared byte by byte.
+        /// Like every function of this module it must run on the thread GTK was
+        /// initialized on.
+        #[derive(Debug)]
+        pub struct ClipboardWatcher {
+            interval: Duration,
+            last_check: Option<Instant>,
+            last_hash: Option<u64>,
+        }
+        impl ClipboardWatcher {
+            /// Checks at most once per `interval`, what the clipboard holds now doesn't
+            /// count as a change
+            pub fn new(interval: Duration) -> ClipboardWatcher {
+                ClipboardWatcher {
+                    interval,
+                    last_check: None,
+                    last_hash: Some(snapshot_hash(&get_snapshot())),
+                }
+            }
+            pub fn interval(&self) -> Duration {
+                self.interval
+            }
+            pub fn set_interval(&mut self, interval: Duration) {
+                self.interval = interval;
+            }
+            /// Waits until `interval` passed since the previous check, then checks once.
+            /// Returns `None` when nothing changed, so `loop { watcher.poll() }` checks at
+            /// the watcher's pace.
+            pub fn poll(&mut self) -> Option<ClipboardEvent> {
+                if let Some(last_check) = self.last_check {
+                    std::thread::sleep(self.interval.saturating_sub(last_check.elapsed()));
+                }
+                self.last_check = Some(Instant::now());
+                let snapshot = get_snapshot();
+                let hash = Some(snapshot_hash(&snapshot));
+                if hash == self.last_hash {
+                    return None;
+                }
+                self.last_hash = hash;
+                if snapshot.is_empty() {
+                    return Some(ClipboardEvent::Cleared);
+                }
+                Some(ClipboardEvent::Changed(snapshot))
+            }
+        }
+        impl Default for ClipboardWatcher {
+            fn default() -> Self {
+                ClipboardWatcher::new(DEFAULT_WATCH_INTERVAL)
+            }
+        }
+    }
+
+    pub mod apps {
+
+        use std::collections::HashMap;
+        use std::io::{BufRead, BufReader, Lines};
+        use std::path::{Path, PathBuf};
+        use std::pin::Pin;
+        use std::process::{Child, ChildStdout, Command, Stdio};
+        use std::sync::{Arc, Mutex};
+        use std::task::{Context, Poll, Waker};
+
+        use futures_util::Stream;
+        use gio::glib::{home_dir, GString, MainContext, MainLoop};
+        use gio::prelude::*;
+        use gio::{AppInfo, DesktopAppInfo};
+        use gtk::{prelude::*, IconLookupFlags};
+        use gtk::{IconTheme, RecentManager};
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::image_utils::{self, ImageFormat};
+        use crate::handlers::easy_gnome::window;
+
+        pub struct App {
+            pub name: GString,
+            pub description: Option<GString>,
+            pub icon: Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
+            /// Themed icon name, or a path for apps shipping their own icon file
+            pub icon_name: Option<String>,
+            pub executable: PathBuf,
+            pub desktop_file: Option<PathBuf>,
+            pub flatpak_id: Option<String>,
+            /// Size `icon` was loaded at
+            icon_size: i32,
+            icon_cache: Mutex<HashMap<i32, Option<image::RgbaImage>>>,
+        }
+        impl App {
+            /// Name in the user's language, GLib picks the `Name[xx]` entry of the desktop
+            /// file from `LANGUAGE`, `LC_ALL`, `LC_MESSAGES` and `LANG`, in that order
+            pub fn get_name(&self) -> &GString {
+                &self.name
+            }
+            /// `Comment` in the user's language, see `get_name`
+            pub fn get_description(&self) -> &Option<GString> {
+                &self.description
+            }
+            /// Localized entry of the desktop file, with GLib's fallbacks for `locale`
+            fn desktop_entry(&self, key: &str, locale: &str) -> Option<String> {
+                let key_file = gio::glib::KeyFile::new();
+                key_file
+                    .load_from_file(self.desktop_file.as_ref()?, gio::glib::KeyFileFlags::NONE)
+                    .ok()?;
+                key_file
+                    .locale_string("Desktop Entry", key, Some(locale))
+                    .ok()
+                    .map(|value| value.to_string())
+            }
+            /// ## Name in another language
+            /// `locale` is like `pt_BR` or `de`, `pt_BR` falls back to `pt` and then to the
+            /// untranslated `Name`. Apps without a desktop file give `get_name`.
+            pub fn name_for_locale(&self, locale: &str) -> String {
+                self.desktop_entry("Name", locale)
+                    .unwrap_or_else(|| self.name.to_string())
+            }
+            /// `Comment` in another language, see `name_for_locale`
+            pub fn description_for_locale(&self, locale: &str) -> Option<String> {
+                self.desktop_entry("Comment", locale)
+                    .or_else(|| self.description.as_ref().map(|d| d.to_string()))
+            }
+            pub fn get_icon(&self) -> &Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+                &self.icon
+            }
+            /// ## Icon rendered at another size
+            /// Loaded from the icon theme rather than scaled from `icon`, so small sizes stay
+            /// crisp. Every size is only loaded once. Like `Apps::new` it needs GTK initialized.
+            pub fn icon_at_size(
+                &self,
+                size: i32,
+            ) -> Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+                if self.icon.is_some() && size == self.icon_size {
+                    return self.icon.clone();
+                }
+                let icon_name = self.icon_name.as_ref()?;
+                let mut cache = self.icon_cache.lock().unwrap();
+                cache
+                    .entry(size)
+                    .or_insert_with(|| load_icon(&load_icon_theme(), icon_name, size))
+                    .clone()
+            }
+            /// ## Icon file on disk
+            /// The SVG or PNG the icon theme resolves `icon_name` to, for UIs that load icons
+            /// themselves. `None` when the app has no icon or it isn't backed by a file.
+            pub fn icon_path(&self) -> Option<PathBuf> {
+                let icon_name = self.icon_name.as_ref()?;
+                let path = Path::new(icon_name);
+                if path.is_absolute() {
+                    return path.exists().then(|| path.to_path_buf());
+                }
+                load_icon_theme()
+                    .lookup_icon(icon_name, ICON_SIZE, IconLookupFlags::empty())?
+                    .filename()
+            }
+            pub fn get_base64_icon(&self) -> Option<String> {
+                self.get_base64_icon_as(ImageFormat::Png)
+            }
+            /// Same as `get_base64_icon` in another format, WebP is a lot smaller than PNG
+            pub fn get_base64_icon_as(&self, format: ImageFormat) -> Option<String> {
+                let image_data = self.get_icon_bytes(format)?;
+                let res_base64 = base64::encode(image_data);
+                Some(format!("data:{};base64,{}", format.mime_type(), res_base64))
+            }
+            /// The icon encoded as `format`, `None` without an icon or when it can't be encoded
+            pub fn get_icon_bytes(&self, format: ImageFormat) -> Option<Vec<u8>> {
+                let icon = self.icon.as_ref()?;
+                image_utils::encode(icon, format).ok()
+            }
+            /// The icon as a PNG fitting in a `max_dim` square, scaled down from `icon`
+            pub fn get_icon_thumbnail(&self, max_dim: u32) -> Option<Vec<u8>> {
+                let icon = self.icon.as_ref()?;
+                image_utils::encode(&image_utils::scale_down(icon, max_dim), ImageFormat::Png).ok()
+            }
+            /// Terminal apps (`Terminal=true`) are started in the default terminal, see
+            /// `run_in_terminal`
+            pub fn launch(&self) -> Result<()> {
+                let info = self.app_info().ok_or(Error::NotSupported)?;
+                if self.is_terminal_app() {
+                    let commandline = info.commandline().ok_or(Error::NotSupported)?;
+                    return run_in_terminal(&strip_field_codes(&commandline.to_string_lossy()));
+                }
+                info.launch(&[], Some(&launch_context()))?;
+                Ok(())
+            }
+            /// ## Launch with extra environment variables and working directory
+            /// `env` is added to the environment the app inherits, e.g. `LANG` or
+            /// `DRI_PRIME=1` to use the discrete GPU.
+            /// `cwd` replaces the `Path` of the desktop file. It's ignored for D-Bus activated
+            /// apps (`DBusActivatable=true`), which D-Bus starts, and `Error::NotSupported` is
+            /// returned with it for apps without a desktop file.
+            pub fn launch_with_context(
+                &self,
+                env: HashMap<String, String>,
+                cwd: Option<PathBuf>,
+            ) -> Result<()> {
+                let info = self.app_info().ok_or(Error::NotSupported)?;
+                let context = launch_context();
+                for (name, value) in &env {
+                    context.setenv(name, value);
+                }
+                let info = match info.downcast::<DesktopAppInfo>() {
+                    Ok(info) => info,
+                    Err(info) => {
+                        if cwd.is_some() {
+                            return Err(Error::NotSupported);
+                        }
+                        info.launch(&[], Some(&context))?;
+                        return Ok(());
+                    }
+                };
+                // Runs in the child right before exec, after GLib moved to the `Path` directory
+                let user_setup = cwd.map(|cwd| {
+                    Box::new(move || {
+                        let _ = std::env::set_current_dir(cwd);
+                    }) as Box<dyn FnOnce()>
+                });
+                info.launch_uris_as_manager(
+                    &[],
+                    Some(&context),
+                    gio::glib::SpawnFlags::SEARCH_PATH,
+                    user_setup,
+                    None,
+                )?;
+                Ok(())
+            }
+            /// `StartupWMClass` of the desktop file, the window class the app's windows will
+            /// have when it differs from the desktop id. Match it against
+            /// `window::WindowInfo::wm_class` to find the window of a launched app.
+            pub fn startup_wm_class(&self) -> Option<String> {
+                let info = DesktopAppInfo::from_filename(self.desktop_file.as_ref()?)?;
+                info.startup_wm_class().map(|wm_class| wm_class.to_string())
+            }
+            /// Desktop id like `org.gnome.Calculator.desktop`, the desktop file name
+            fn desktop_id(&self) -> Option<String> {
+                let file_name = self.desktop_file.as_ref()?.file_name()?;
+                Some(file_name.to_string_lossy().to_string())
+            }
+            /// ## Whether a window of the app is open
+            /// For launchers that focus the app instead of starting it again. Windows are
+            /// matched by desktop id, `StartupWMClass` or a window class named like the app.
+            /// Relies on `window::list_windows`, so on Wayland it needs the shell in unsafe
+            /// mode, `false` is returned when windows can't be listed.
+            pub async fn is_running(&self) -> bool {
+                let windows = match window::list_windows().await {
+                    Ok(windows) => windows,
+                    Err(_) => return false,
+                };
+                let desktop_id = self.desktop_id();
+                let wm_class = self.startup_wm_class();
+                let id_class = desktop_id
+                    .as_ref()
+                    .map(|id| id.trim_end_matches(".desktop").to_string());
+                windows.iter().any(|window| {
+                    (window.app_id.is_some() && window.app_id == desktop_id)
+                        || [&wm_class, &id_class].iter().any(|class| match class {
+                            Some(class) => class.eq_ignore_ascii_case(&window.wm_class),
+                            None => false,
+                        })
+                })
+            }
+            /// Whether the app runs in a terminal, `Terminal=true` in its desktop file
+            pub fn is_terminal_app(&self) -> bool {
+                self.desktop_file
+                    .as_ref()
+                    .and_then(DesktopAppInfo::from_filename)
+                    .map(|info| info.boolean("Terminal"))
+                    .unwrap_or(false)
+            }
+            /// Whether the app was installed with flatpak, user or system wide
+            pub fn is_flatpak(&self) -> bool {
+                self.flatpak_id.is_some()
+            }
+            /// The flatpak application id, e.g. `org.gnome.Calculator`
+            pub fn flatpak_id(&self) -> Option<String> {
+                self.flatpak_id.clone()
+            }
+            /// The gio app this was built from, looked up by desktop file or by name
+            fn app_info(&self) -> Option<AppInfo> {
+                if let Some(file) = &self.desktop_file {
+                    if let Some(info) = DesktopAppInfo::from_filename(file) {
+                        return Some(info.upcast());
+                    }
+                }
+                AppInfo::all()
+                    .into_iter()
+                    .find(|app| app.name().eq_ignore_ascii_case(&self.name))
+            }
+            /// Make this app the one opening `mime` files, e.g. `image/png`
+            pub fn set_as_default_for(&self, mime: &str) -> Result<()> {
+                let info = self.app_info().ok_or(Error::NotSupported)?;
+                info.set_as_default_for_type(mime)?;
+                Ok(())
+            }
+            /// ## Uninstall the app, waiting until it's done
+            /// Only flatpak apps can be uninstalled, others return `Error::NotSupported`.
+            /// See `uninstall_with_progress` to show what's going on.
+            pub fn uninstall(&self) -> Result<()> {
+                self.uninstall_with_progress()?.finish()
+            }
+            /// ## Start uninstalling the app
+            /// Iterate the returned value to get the lines `flatpak` prints as it goes, then
+            /// call `finish` to get the result.
+            /// The installation (user or system) is picked from where the desktop file lives.
+            pub fn uninstall_with_progress(&self) -> Result<UninstallProgress> {
+                let id = self.flatpak_id.as_ref().ok_or(Error::NotSupported)?;
+                let [user_dir, system_dir] = flatpak_exports_dirs();
+                let mut command = Command::new("flatpak");
+                command.args(["uninstall", "--noninteractive", "-y"]);
+                match &self.desktop_file {
+                    Some(file) if file.starts_with(user_dir) => {
+                        command.arg("--user");
+                    }
+                    Some(file) if file.starts_with(system_dir) => {
+                        command.arg("--system");
+                    }
+                    _ => {}
+                }
+                let mut child = command
+                    .arg(id)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
+                let stdout = child.stdout.take().unwrap();
+                Ok(UninstallProgress {
+                    child,
+                    lines: BufReader::new(stdout).lines(),
+                })
+            }
+        }
+
+        /// A running `flatpak uninstall`, yields its output line by line
+        pub struct UninstallProgress {
+            child: Child,
+            lines: Lines<BufReader<ChildStdout>>,
+        }
+        impl Iterator for UninstallProgress {
+            type Item = String;
+            fn next(&mut self) -> Option<String> {
+                self.lines.next()?.ok()
+            }
+        }
+        impl UninstallProgress {
+            /// Waits for the uninstall to end, the remaining output is discarded
+            pub fn finish(self) -> Result<()> {
+                let UninstallProgress { child, lines } = self;
+                lines.for_each(drop);
+                let output = child.wait_with_output()?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    return Err(Error::Command(stderr.trim().to_string()));
+                }
+                Ok(())
+            }
+        }
+
+        /// `share` dirs flatpak exports desktop files and icons to, user install first
+        fn flatpak_exports_dirs() -> [PathBuf; 2] {
+            [
+                home_dir().join(".local/share/flatpak/exports/share"),
+                PathBuf::from("/var/lib/flatpak/exports/share"),
+            ]
+        }
+        /// Desktop files exported by flatpak that gio doesn't know about, which happens when
+        /// the exports dirs are missing from `XDG_DATA_DIRS` (e.g. apps started from systemd)
+        fn missing_flatpak_apps(known: &[AppInfo]) -> Vec<AppInfo> {
+            let mut missing: Vec<AppInfo> = Vec::new();
+            for dir in flatpak_exports_dirs() {
+                let entries = match std::fs::read_dir(dir.join("applications")) {
+                    Ok(entries) => entries,
+                    Err(_) => continue,
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension() != Some(std::ffi::OsStr::new("desktop")) {
+                        continue;
+                    }
+                    let info = match DesktopAppInfo::from_filename(&path) {
+                        Some(info) => info.upcast::<AppInfo>(),
+                        None => continue,
+                    };
+                    // User installs shadow system ones, like in XDG_DATA_DIRS
+                    let id = info.id();
+                    if known.iter().chain(missing.iter()).any(|app| app.id() == id) {
+                        continue;
+                    }
+                    missing.push(info);
+                }
+            }
+            missing
+        }
+        fn read_flatpak_id(
+            desktop_file: &Option<PathBuf>,
+            info: Option<&DesktopAppInfo>,
+        ) -> Option<String> {
+            if let Some(id) = info.and_then(|info| info.string("X-Flatpak")) {
+                return Some(id.to_string());
+            }
+            // Older flatpak versions don't write X-Flatpak, the exported file is named after the app
+            let desktop_file = desktop_file.as_ref()?;
+            let is_exported = flatpak_exports_dirs()
+                .iter()
+                .any(|dir| desktop_file.starts_with(dir.join("applications")));
+            if !is_exported {
+                return None;
+            }
+            desktop_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        }
+
+        pub struct Apps {
+            pub apps: Vec<App>,
+        }
+
+        const ICON_SIZE: i32 = 128;
+
+        fn load_icon_theme() -> IconTheme {
+            let icon_theme: IconTheme = IconTheme::default().unwrap();
+            icon_theme.add_resource_path(
+                format!(
+                    "{}/.local/share/icons/hicolor",
+                    home_dir().to_str().unwrap()
+                )
+                .as_str(),
+            );
+            for dir in flatpak_exports_dirs() {
+                icon_theme.append_search_path(dir.join("icons"));
+            }
+            icon_theme
+        }
+        /// Rasterizes a themed icon, falling back to the `info` icon when it's missing,
+        /// `None` when neither loads
+        fn load_icon(
+            icon_theme: &IconTheme,
+            icon_name: &str,
+            size: i32,
+        ) -> Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+            // Transform icon name to pixbuf
+            let pixbuf = icon_theme
+                .load_icon(icon_name, size, IconLookupFlags::GENERIC_FALLBACK)
+                .or_else(|_| icon_theme.load_icon("info", size, IconLookupFlags::GENERIC_FALLBACK))
+                .ok()??;
+            let (width, height) = (pixbuf.width() as u32, pixbuf.height() as u32);
+            // Pix buf are cuadruplets of u8 (rgba)
+            let bytes: Vec<u8> = pixbuf.read_pixel_bytes()?.to_vec();
+
+            // Using image library build a png based on cuadruplets (rgba)
+            let image = image::RgbaImage::from_vec(width, height, bytes)?;
+            // Icons shipped as a file keep their own size
+            Some(image_utils::scale_down(&image, size as u32))
+        }
+        /// Returns `None` when the icon can't be turned into an image
+        fn app_from_info(app: &AppInfo, icon_theme: &IconTheme) -> Option<App> {
+            app_from_info_at(app, Some(icon_theme), ICON_SIZE)
+        }
+        /// Same as `app_from_info`, without `icon_theme` the icon isn't loaded
+        fn app_from_info_at(
+            app: &AppInfo,
+            icon_theme: Option<&IconTheme>,
+            icon_size: i32,
+        ) -> Option<App> {
+            let name = app.name();
+            let description = app.description();
+            let icon_name = app
+                .icon()
+                .and_then(|icon| gio::prelude::IconExt::to_string(&icon))
+                .map(|icon_name| icon_name.to_string());
+            let executable = app.executable();
+            let desktop_info = app.downcast_ref::<DesktopAppInfo>();
+            let desktop_file = desktop_info.and_then(|info| info.filename());
+            let flatpak_id = read_flatpak_id(&desktop_file, desktop_info);
+            let icon = match (&icon_name, icon_theme) {
+                (Some(icon_name), Some(icon_theme)) => {
+                    Some(load_icon(icon_theme, icon_name, icon_size)?)
+                }
+                _ => None,
+            };
+
+            Some(App {
+                name,
+                description,
+                icon,
+                icon_name,
+                executable,
+                desktop_file,
+                flatpak_id,
+                icon_size,
+                icon_cache: Mutex::new(HashMap::new()),
+            })
+        }
+
+        impl Apps {
+            pub fn get_apps(&self) -> &Vec<App> {
+                &self.apps
+            }
+            /// ## Apps matching a search query
+            /// Case insensitive, every word of `query` must appear in the name, description
+            /// or executable. Apps whose name starts with the query come first.
+            pub fn search(&self, query: &str) -> Vec<&App> {
+                let query = query.to_lowercase();
+                let words: Vec<&str> = query.split_whitespace().collect();
+                let mut found: Vec<&App> = self
+                    .apps
+                    .iter()
+                    .filter(|app| {
+                        let haystack = format!(
+                            "{} {} {}",
+                            app.name,
+                            app.description.as_deref().unwrap_or_default(),
+                            app.executable.to_string_lossy()
+                        )
+                        .to_lowercase();
+                        words.iter().all(|word| haystack.contains(word))
+                    })
+                    .collect();
+                found.sort_by_key(|app| !app.name.to_lowercase().starts_with(&query));
+                found
+            }
+
+            /// Every app shown in the overview, with its icon at 128px.
+            /// See `Apps::builder` to skip the icons or filter the apps.
+            pub fn new() -> Apps {
+                AppsBuilder::new().build()
+            }
+            pub fn builder() -> AppsBuilder {
+                AppsBuilder::new()
+            }
+        }
+
+        /// ## Enumerate apps with options
+        /// Loading icons is the slow part, skip it with `load_icons(false)` when only names
+        /// are needed, `App::icon_at_size` can still load them later.
+        #[derive(Debug, Clone)]
+        pub struct AppsBuilder {
+            icon_size: i32,
+            load_icons: bool,
+            include_hidden: bool,
+            categories: Vec<String>,
+        }
+        impl AppsBuilder {
+            pub fn new() -> AppsBuilder {
+                AppsBuilder {
+                    icon_size: ICON_SIZE,
+                    load_icons: true,
+                    include_hidden: false,
+                    categories: Vec::new(),
+                }
+            }
+            /// Size of `App::icon` in pixels, 128 by default
+            pub fn icon_size(mut self, size: i32) -> AppsBuilder {
+                self.icon_size = size;
+                self
+            }
+            pub fn load_icons(mut self, load: bool) -> AppsBuilder {
+                self.load_icons = load;
+                self
+            }
+            /// Also list apps hidden from the overview (`NoDisplay=true` or not meant for
+            /// this desktop)
+            pub fn include_hidden(mut self, include: bool) -> AppsBuilder {
+                self.include_hidden = include;
+                self
+            }
+            /// Only apps in at least one of `categories`, e.g. `&["Game", "AudioVideo"]`
+            /// (freedesktop main categories). Apps without categories are left out.
+            pub fn only_categories(mut self, categories: &[&str]) -> AppsBuilder {
+                self.categories = categories.iter().map(|c| c.to_string()).collect();
+                self
+            }
+            fn in_categories(&self, app: &AppInfo) -> bool {
+                if self.categories.is_empty() {
+                    return true;
+                }
+                app.downcast_ref::<DesktopAppInfo>()
+                    .and_then(|info| info.categories())
+                    .map(|categories| {
+                        categories
+                            .split(';')
+                            .any(|category| self.categories.iter().any(|c| c == category))
+                    })
+                    .unwrap_or(false)
+            }
+            pub fn build(self) -> Apps {
+                let mut infos = AppInfo::all();
+                infos.extend(missing_flatpak_apps(&infos));
+                let icon_theme = self.load_icons.then(load_icon_theme);
+                let apps: Vec<App> = infos
+                    .iter()
+                    .filter(|app| self.include_hidden || app.should_show())
+                    .filter(|app| self.in_categories(app))
+                    .filter_map(|app| app_from_info_at(app, icon_theme.as_ref(), self.icon_size))
+                    .collect();
+                Apps { apps }
+            }
+        }
+        impl Default for AppsBuilder {
+            fn default() -> Self {
+                AppsBuilder::new()
+            }
+        }
+
+        /// The installed apps changed, enumerate them again to see how
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub struct AppsChanged;
+
+        /// Whether a change is waiting to be emitted, and the task to wake for it
+        type WatchState = Arc<Mutex<(bool, Option<Waker>)>>;
+
+        /// Stream returned by `watch`, stops its thread when dropped
+        struct AppsWatch {
+            state: WatchState,
+            context: MainContext,
+            main_loop: MainLoop,
+        }
+        impl Stream for AppsWatch {
+            type Item = AppsChanged;
+
+            fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<AppsChanged>> {
+                let mut state = self.state.lock().unwrap();
+                if state.0 {
+                    state.0 = false;
+                    return Poll::Ready(Some(AppsChanged));
+                }
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+        impl Drop for AppsWatch {
+            fn drop(&mut self) {
+                // Quitting from the loop's own context also works when it hasn't started yet
+                let main_loop = self.main_loop.clone();
+                self.context.invoke(move || main_loop.quit());
+            }
+        }
+
+        /// ## Emit when apps are installed, removed or updated
+        /// From `gio::AppInfoMonitor`, which watches the desktop file directories. Re-run
+        /// `Apps::new` on every item. Changes made while the previous item wasn't consumed
+        /// yet are merged into one.
+        ///
+        /// The monitor runs its own GLib main loop on a thread, no main loop is needed in
+        /// the consumer.
+        pub fn watch() -> impl Stream<Item = AppsChanged> {
+            let state: WatchState = Arc::new(Mutex::new((false, None)));
+            let context = MainContext::new();
+            let main_loop = MainLoop::new(Some(&context), false);
+            let thread_state = state.clone();
+            let thread_context = context.clone();
+            let thread_loop = main_loop.clone();
+            std::thread::spawn(move || {
+                let _ = thread_context.with_thread_default(|| {
+                    let monitor = gio::AppInfoMonitor::get();
+                    monitor.connect_changed(move |_| {
+                        let mut state = thread_state.lock().unwrap();
+                        state.0 = true;
+                        if let Some(waker) = state.1.take() {
+                            waker.wake();
+                        }
+                    });
+                    thread_loop.run();
+                });
+            });
+            AppsWatch {
+                state,
+                context,
+                main_loop,
+            }
+        }
+
+        /// ## Default app for a MIME type
+        /// e.g. `text/plain` or `x-scheme-handler/https`, `None` when there is none or the type
+        /// is unknown. Like `Apps::new`, GTK must be initialized to load the icon.
+        pub fn default_for_mime(mime: &str) -> Option<App> {
+            let info = AppInfo::default_for_type(mime, false)?;
+            app_from_info(&info, &load_icon_theme())
+        }
+        /// ## Every app that can open a MIME type
+        /// The default one comes first, unknown types give an empty list.
+        pub fn all_for_mime(mime: &str) -> Vec<App> {
+            let icon_theme = load_icon_theme();
+            AppInfo::all_for_type(mime)
+                .iter()
+                .filter_map(|info| app_from_info(info, &icon_theme))
+                .collect()
+        }
+        /// Types GNOME Settings' "Default Apps" panel assigns to the web browser
+        const BROWSER_TYPES: [&str; 4] = [
+            "x-scheme-handler/http",
+            "x-scheme-handler/https",
+            "text/html",
+            "application/xhtml+xml",
+        ];
+        const MAIL_TYPES: [&str; 1] = ["x-scheme-handler/mailto"];
+
+        /// `app_id` is a desktop id like `firefox.desktop`
+        fn desktop_app_info(app_id: &str) -> Result<DesktopAppInfo> {
+            DesktopAppInfo::new(app_id)
+                .ok_or_else(|| Error::InvalidArgument(format!("No app with id {}", app_id)))
+        }
+        fn set_default_for_types(app_id: &str, types: &[&str]) -> Result<()> {
+            let info = desktop_app_info(app_id)?;
+            for mime in types {
+                info.set_as_default_for_type(mime)?;
+            }
+            Ok(())
+        }
+        /// ## Make an app the default web browser
+        /// `app_id` is a desktop id like `firefox.desktop`
+        pub fn set_default_browser(app_id: &str) -> Result<()> {
+            set_default_for_types(app_id, &BROWSER_TYPES)
+        }
+        pub fn get_default_browser() -> Option<App> {
+            default_for_mime(BROWSER_TYPES[1])
+        }
+        /// Make an app the one opening `mailto:` links
+        pub fn set_default_mail(app_id: &str) -> Result<()> {
+            set_default_for_types(app_id, &MAIL_TYPES)
+        }
+        pub fn get_default_mail() -> Option<App> {
+            default_for_mime(MAIL_TYPES[0])
+        }
+        /// ## Make an app the default terminal
+        /// Terminals have no MIME type, GNOME reads the executable from the
+        /// `org.gnome.desktop.default-applications.terminal` `exec` key instead.
+        pub fn set_default_terminal(app_id: &str) -> Result<()> {
+            let info = desktop_app_info(app_id)?;
+            let executable = info.executable();
+            crate::dconf::set(
+                "org.gnome.desktop.default-applications.terminal",
+                "exec",
+                &crate::dconf::format_string(&executable.to_string_lossy()),
+            )
+        }
+        /// GDK's launch context when GTK is initialized, which sets up the startup
+        /// notification: GNOME shows the app as starting and the cursor spins until its
+        /// window appears. A plain GIO context otherwise.
+        fn launch_context() -> gio::AppLaunchContext {
+            match gdk::Display::default().and_then(|display| display.app_launch_context()) {
+                Some(context) => context.upcast(),
+                None => gio::AppLaunchContext::new(),
+            }
+        }
+        const TERMINAL_SCHEMA: &str = "org.gnome.desktop.default-applications.terminal";
+
+        /// Removes the `%f`, `%U`… placeholders of a desktop file `Exec` line, nothing is
+        /// passed to the app
+        fn strip_field_codes(commandline: &str) -> String {
+            commandline
+                .split_whitespace()
+                .filter(|arg| !(arg.len() == 2 && arg.starts_with('%')))
+                .collect::<Vec<&str>>()
+                .join(" ")
+        }
+        /// ## Run a command in the default terminal
+        /// The terminal is read from `org.gnome.desktop.default-applications.terminal`
+        /// (`exec` and `exec-arg`, the flag that makes it run a command), see
+        /// `set_default_terminal`. `command` is run by `sh -c`.
+        pub fn run_in_terminal(command: &str) -> Result<()> {
+            let terminal = crate::dconf::get_string(TERMINAL_SCHEMA, "exec")?;
+            if terminal.is_empty() {
+                return Err(Error::NotSupported);
+            }
+            let exec_arg = crate::dconf::get_string(TERMINAL_SCHEMA, "exec-arg")?;
+            let mut cmd = Command::new(terminal);
+            if !exec_arg.is_empty() {
+                cmd.arg(exec_arg);
+            }
+            cmd.args(["sh", "-c", command]).spawn()?;
+            Ok(())
+        }
+        /// ## Open a URI with the default app for it
+        /// Works for any scheme with a handler, e.g. `https://`, `mailto:` or `file://`
+        pub fn open_uri(uri: &str) -> Result<()> {
+            AppInfo::launch_default_for_uri(uri, None::<&gio::AppLaunchContext>)?;
+            Ok(())
+        }
+        /// Same as `open_uri` for a local file or folder
+        pub fn open_path(path: &Path) -> Result<()> {
+            open_uri(&gio::File::for_path(path).uri())
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct RecentFile {
+            pub uri: String,
+            pub display_name: String,
+            pub mime_type: Option<String>,
+            /// Unix timestamp in seconds of the last time the file was opened or modified
+            pub last_used: i64,
+        }
+        /// ## Recently used files
+        /// The list GNOME shows in file dialogs and Files' "Recent" view, most recently used
+        /// first. GTK must be initialized.
+        pub fn recent_files() -> Vec<RecentFile> {
+            let manager = match RecentManager::default() {
+                Some(manager) => manager,
+                None => return vec![],
+            };
+            let mut files: Vec<RecentFile> = manager
+                .items()
+                .iter()
+                .filter_map(|info| {
+                    let uri = info.uri()?.to_string();
+                    Some(RecentFile {
+                        display_name: info
+                            .display_name()
+                            .map(|name| name.to_string())
+                            .unwrap_or_else(|| uri.clone()),
+                        uri,
+                        mime_type: info.mime_type().map(|mime| mime.to_string()),
+                        last_used: info.modified().max(info.visited()) as i64,
+                    })
+                })
+                .collect();
+            files.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+            files
+        }
+        /// ## Add a file to the recently used list
+        /// Returns `false` when the uri isn't valid. GTK must be initialized.
+        pub fn add_recent_file(uri: &str) -> bool {
+            let manager = match RecentManager::default() {
+                Some(manager) => manager,
+                None => return false,
+            };
+            if !manager.add_item(uri) {
+                return false;
+            }
+            // GTK looks up the MIME type asynchronously before adding the item
+            let context = gio::glib::MainContext::default();
+            while !manager.has_item(uri) && context.iteration(true) {}
+            flush_recent_manager(&context);
+            true
+        }
+        /// ## Remove a file from the recently used list
+        /// Fails when the uri isn't in the list. GTK must be initialized.
+        pub fn remove_recent_file(uri: &str) -> Result<()> {
+            let manager = RecentManager::default().ok_or(Error::NotSupported)?;
+            manager.remove_item(uri)?;
+            flush_recent_manager(&gio::glib::MainContext::default());
+            Ok(())
+        }
+        /// GTK writes `recently-used.xbel` from an idle callback, run it before returning
+        fn flush_recent_manager(context: &gio::glib::MainContext) {
+            while context.pending() {
+                context.iteration(false);
+            }
+        }
+    }
+
+    /// # Search provider
+    /// Makes a consumer appear in the search of GNOME's overview by serving the
+    /// `org.gnome.Shell.SearchProvider2` interface on the session bus.
+    ///
+    /// GNOME Shell only queries providers listed in
+    /// `/usr/share/gnome-shell/search-providers/*.ini`, next to a `.desktop` file for the app:
+    /// ```ini
+    /// [Shell Search Provider]
+    /// DesktopId=my-app.desktop
+    /// BusName=org.example.MyApp.SearchProvider
+    /// ObjectPath=/org/example/MyApp/SearchProvider
+    /// Version=2
+    /// ```
+    pub mod search_provider {
+        use std::collections::HashMap;
+
+        use zbus::{dbus_interface, Connection, ConnectionBuilder};
+        use zvariant::{OwnedValue, Value};
+
+        use crate::error::Result;
+
+        /// What the overview shows for a result
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct ResultMeta {
+            pub id: String,
+            pub name: String,
+            pub description: Option<String>,
+            /// Themed icon name or icon path
+            pub icon: Option<String>,
+        }
+
+        pub trait SearchProvider: Send + Sync + 'static {
+            /// Ids of the results for a new search
+            fn initial_result_set(&self, terms: &[String]) -> Vec<String>;
+            /// Ids of the results when the user keeps typing, `previous_results` are the ids
+            /// returned for the shorter terms
+            fn subsearch_result_set(
+                &self,
+                previous_results: &[String],
+                terms: &[String],
+            ) -> Vec<String> {
+                let _ = previous_results;
+                self.initial_result_set(terms)
+            }
+            /// Metadata of the results the overview is about to show
+            fn result_metas(&self, ids: &[String]) -> Vec<ResultMeta>;
+            /// The user clicked a result
+            fn activate_result(&self, id: &str, terms: &[String], timestamp: u32);
+            /// The user clicked the provider icon to see every result in the app
+            fn launch_search(&self, terms: &[String], timestamp: u32) {
+                let _ = (terms, timestamp);
+            }
+        }
+
+        struct SearchProviderServer<P: SearchProvider> {
+            provider: P,
+        }
+
+        #[dbus_interface(name = "org.gnome.Shell.SearchProvider2")]
+        impl<P: SearchProvider> SearchProviderServer<P> {
+            fn get_initial_result_set(&self, terms: Vec<String>) -> Vec<String> {
+                self.provider.initial_result_set(&terms)
+            }
+            fn get_subsearch_result_set(
+                &self,
+                previous_results: Vec<String>,
+                terms: Vec<String>,
+            ) -> Vec<String> {
+                self.provider
+                    .subsearch_result_set(&previous_results, &terms)
+            }
+            fn get_result_metas(
+                &self,
+                identifiers: Vec<String>,
+            ) -> Vec<HashMap<String, OwnedValue>> {
+                self.provider
+                    .result_metas(&identifiers)
+                    .into_iter()
+                    .map(|meta| {
+                        let mut map = HashMap::new();
+                        map.insert("id".to_string(), Value::from(meta.id).into());
+                        map.insert("name".to_string(), Value::from(meta.name).into());
+                        if let Some(description) = meta.description {
+                            map.insert("description".to_string(), Value::from(description).into());
+                        }
+                        if let Some(icon) = meta.icon {
+                            map.insert("gicon".to_string(), Value::from(icon).into());
+                        }
+                        map
+                    })
+                    .collect()
+            }
+            fn activate_result(&self, identifier: String, terms: Vec<String>, timestamp: u32) {
+                self.provider
+                    .activate_result(&identifier, &terms, timestamp)
+            }
+            fn launch_search(&self, terms: Vec<String>, timestamp: u32) {
+                self.provider.launch_search(&terms, timestamp)
+            }
+        }
+
+        /// ## Register a search provider
+        /// Owns `bus_name` and serves `provider` at `object_path` on the session bus, both must
+        /// match the provider `.ini` file. The provider is served until the returned connection
+        /// is dropped.
+        pub async fn register<P: SearchProvider>(
+            bus_name: &str,
+            object_path: &str,
+            provider: P,
+        ) -> Result<Connection> {
+            Ok(ConnectionBuilder::session()?
+                .name(bus_name)?
+                .serve_at(object_path, SearchProviderServer { provider })?
+                .build()
+                .await?)
+        }
+    }
+
+    pub mod battery {
+        use futures_util::{Stream, StreamExt};
+        use upower_dbus::{BatteryState, DeviceProxy, UPowerProxy};
+
+        use crate::error::Result;
+        use crate::watch::watch_property;
+
+        /// What UPower does when the battery reaches the critical level
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum CriticalAction {
+            PowerOff,
+            Hibernate,
+            HybridSleep,
+        }
+        impl CriticalAction {
+            fn from(action: &str) -> CriticalAction {
+                match action {
+                    "Hibernate" => CriticalAction::Hibernate,
+                    "HybridSleep" => CriticalAction::HybridSleep,
+                    _ => CriticalAction::PowerOff,
+                }
+            }
+        }
+
+        pub async fn get_critical_action() -> Result<CriticalAction> {
+            let connection = zbus::Connection::system().await?;
+            let upower = UPowerProxy::new(&connection).await?;
+            let action = upower.get_critical_action().await?;
+            Ok(CriticalAction::from(action.as_str()))
+        }
+        /// Whether the system is running on battery power
+        pub async fn on_battery() -> Result<bool> {
+            let connection = zbus::Connection::system().await?;
+            let upower = UPowerProxy::new(&connection).await?;
+            Ok(upower.on_battery().await?)
+        }
+        // Get devices with battery stats
+        pub async fn get_current_device_battery() -> Result<DeviceProxy<'static>> {
             let connection = zbus::Connection::system().await?;
             let upower = UPowerProxy::new(&connection).await?;
             let device: DeviceProxy<'_> = upower.get_display_device().await?;
             Ok(device)
         }
-        pub async fn get_devices_battery() -> zbus::Result<Vec<DeviceProxy<'static>>> {
-            let connection = zbus::Connection::system().await?;
-            let upower = UPowerProxy::new(&connection).await?;
-            let devices: Vec<zvariant::OwnedObjectPath> = upower.enumerate_devices().await?;
+        pub async fn get_devices_battery() -> Result<Vec<DeviceProxy<'static>>> {
+            let connection = zbus::Connection::system().await?;
+            let upower = UPowerProxy::new(&connection).await?;
+            let devices: Vec<zvariant::OwnedObjectPath> = upower.enumerate_devices().await?;
+
+            let mut devices_battery: Vec<DeviceProxy<'_>> = Vec::new();
+            for device in devices {
+                let device_proxy = DeviceProxy::new(&connection, device).await?;
+                let is_rechargable = device_proxy.is_rechargeable().await?;
+                if is_rechargable {
+                    devices_battery.push(device_proxy);
+                }
+            }
+            Ok(devices_battery)
+        }
+        /// Emits the battery percentage of the display device every time it changes
+        pub async fn watch_percentage() -> Result<impl Stream<Item = f64>> {
+            let device = get_current_device_battery().await?;
+            Ok(watch_property::<f64>(&device, "Percentage").await)
+        }
+        /// Marks thresholds as crossed when the level drops to or below them while discharging,
+        /// and re-arms them once the level rises above them again
+        pub(crate) fn crossed_thresholds(
+            armed: &mut [bool],
+            thresholds: &[u8],
+            percentage: f64,
+            discharging: bool,
+        ) -> Vec<u8> {
+            let mut crossed = Vec::new();
+            for (armed, threshold) in armed.iter_mut().zip(thresholds) {
+                let threshold_percentage = *threshold as f64;
+                if percentage > threshold_percentage {
+                    *armed = true;
+                } else if *armed && discharging {
+                    *armed = false;
+                    crossed.push(*threshold);
+                }
+            }
+            crossed
+        }
+        /// Emits a threshold (e.g. `20` and `10` for `&[20, 10]`) once each time the battery
+        /// drops to or below it while discharging.
+        /// A threshold won't fire again until the level rises above it.
+        pub async fn watch_thresholds(thresholds: &[u8]) -> Result<impl Stream<Item = u8>> {
+            let device = get_current_device_battery().await?;
+            let percentage = device.percentage().await?;
+            let thresholds = thresholds.to_vec();
+            let armed: Vec<bool> = thresholds
+                .iter()
+                .map(|threshold| percentage > *threshold as f64)
+                .collect();
+            let changes = watch_property::<f64>(&device, "Percentage").await;
+
+            Ok(changes
+                .filter_map(move |percentage| {
+                    let device = device.clone();
+                    async move {
+                        let state = device.state().await.ok()?;
+                        Some((percentage, matches!(state, BatteryState::Discharging)))
+                    }
+                })
+                .scan(armed, move |armed, (percentage, discharging)| {
+                    let crossed = crossed_thresholds(armed, &thresholds, percentage, discharging);
+                    async move { Some(futures_util::stream::iter(crossed)) }
+                })
+                .flatten())
+        }
+    }
+
+    /// # System status
+    /// Battery, power and network state merged into one value, for status widgets.
+    pub mod status {
+        use futures_util::stream::{self, BoxStream};
+        use futures_util::{Stream, StreamExt};
+        use upower_dbus::{BatteryState, UPowerProxy};
+        use zbus::Connection;
+
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::{battery, power, NetworkManagerProxy, PowerProfile};
+        use crate::watch::watch_property;
+
+        /// NetworkManager's view of the internet access
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub enum Connectivity {
+            Unknown,
+            /// Not connected to any network
+            None,
+            /// Behind a captive portal, e.g. a hotel wifi login page
+            Portal,
+            /// Connected to a network without internet access
+            Limited,
+            Full,
+        }
+        impl Connectivity {
+            fn from(connectivity: u32) -> Connectivity {
+                match connectivity {
+                    1 => Connectivity::None,
+                    2 => Connectivity::Portal,
+                    3 => Connectivity::Limited,
+                    4 => Connectivity::Full,
+                    _ => Connectivity::Unknown,
+                }
+            }
+        }
+
+        /// Latest known value of every component, `None` when it isn't available,
+        /// e.g. no battery on a desktop or power-profiles-daemon not running
+        #[derive(Debug, Clone, Default)]
+        pub struct SystemStatus {
+            pub battery_percentage: Option<f64>,
+            pub battery_state: Option<BatteryState>,
+            pub power_profile: Option<PowerProfile>,
+            pub on_ac: Option<bool>,
+            pub connectivity: Option<Connectivity>,
+        }
+
+        enum Change {
+            BatteryPercentage(f64),
+            BatteryState(BatteryState),
+            PowerProfile(PowerProfile),
+            OnBattery(bool),
+            Connectivity(u32),
+        }
+        impl SystemStatus {
+            fn apply(&mut self, change: Change) {
+                match change {
+                    Change::BatteryPercentage(percentage) => {
+                        self.battery_percentage = Some(percentage)
+                    }
+                    Change::BatteryState(state) => self.battery_state = Some(state),
+                    Change::PowerProfile(profile) => self.power_profile = Some(profile),
+                    Change::OnBattery(on_battery) => self.on_ac = Some(!on_battery),
+                    Change::Connectivity(connectivity) => {
+                        self.connectivity = Some(Connectivity::from(connectivity))
+                    }
+                }
+            }
+        }
+
+        /// ## Watch the whole system status
+        /// Emits the current status right away, then the updated status every time one of
+        /// its components changes. Components that aren't available stay `None` and are
+        /// not watched.
+        pub async fn watch_system() -> Result<impl Stream<Item = SystemStatus>> {
+            let connection = Connection::system().await?;
+            let mut status = SystemStatus::default();
+            let mut changes: Vec<BoxStream<'static, Change>> = Vec::new();
+
+            if let Ok(device) = battery::get_current_device_battery().await {
+                if device.is_present().await.unwrap_or(false) {
+                    status.battery_percentage = device.percentage().await.ok();
+                    status.battery_state = device.state().await.ok();
+                    let percentages = watch_property::<f64>(&device, "Percentage").await;
+                    changes.push(percentages.map(Change::BatteryPercentage).boxed());
+                    let states = watch_property::<BatteryState>(&device, "State").await;
+                    changes.push(states.map(Change::BatteryState).boxed());
+                }
+            }
+            if power::is_power_profiles_available().await {
+                if let Ok(profiles) = power::watch_power_profile().await {
+                    status.power_profile = power::get_power_profile().await.ok();
+                    changes.push(profiles.map(Change::PowerProfile).boxed());
+                }
+            }
+            let upower = UPowerProxy::new(&connection).await?;
+            if let Ok(on_battery) = upower.on_battery().await {
+                status.on_ac = Some(!on_battery);
+                let on_battery = watch_property::<bool>(&upower, "OnBattery").await;
+                changes.push(on_battery.map(Change::OnBattery).boxed());
+            }
+            let network_manager = NetworkManagerProxy::new(&connection).await?;
+            if let Ok(connectivity) = network_manager.Connectivity().await {
+                status.connectivity = Some(Connectivity::from(connectivity));
+                let connectivity = watch_property::<u32>(&network_manager, "Connectivity").await;
+                changes.push(connectivity.map(Change::Connectivity).boxed());
+            }
+
+            let updates = stream::select_all(changes).scan(status.clone(), |status, change| {
+                status.apply(change);
+                futures_util::future::ready(Some(status.clone()))
+            });
+            Ok(stream::once(futures_util::future::ready(status)).chain(updates))
+        }
+    }
+
+    /// # Storage
+    /// Block devices and filesystems known to UDisks2, with their usage.
+    pub mod storage {
+        use std::collections::HashMap;
+        use std::path::{Path, PathBuf};
+
+        use gio::prelude::*;
+        use gio::{Cancellable, File};
+        use zbus::fdo::ObjectManagerProxy;
+        use zbus::Connection;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::get_value;
+
+        const UDISKS_NAME: &str = "org.freedesktop.UDisks2";
+        const UDISKS_PATH: &str = "/org/freedesktop/UDisks2";
+        const BLOCK_INTERFACE: &str = "org.freedesktop.UDisks2.Block";
+        const FILESYSTEM_INTERFACE: &str = "org.freedesktop.UDisks2.Filesystem";
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct BlockDevice {
+            /// Device file, e.g. `/dev/nvme0n1p2`
+            pub device: String,
+            pub size: u64,
+            /// Filesystem label, empty when there is none
+            pub label: String,
+            /// Content type found by blkid, e.g. `ext4`, `vfat`, `crypto_LUKS` or empty
+            pub id_type: String,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct FilesystemInfo {
+            /// Device file, e.g. `/dev/nvme0n1p2`
+            pub device: String,
+            pub label: String,
+            /// e.g. `ext4`, `btrfs` or `vfat`
+            pub fs_type: String,
+            /// First mount point, `None` when it isn't mounted
+            pub mount_point: Option<PathBuf>,
+            pub total_bytes: u64,
+            /// Only known while mounted
+            pub used_bytes: Option<u64>,
+        }
+
+        type Interfaces = HashMap<String, HashMap<String, zvariant::OwnedValue>>;
+
+        /// Interfaces and properties of every object exported by UDisks2
+        async fn managed_objects() -> Result<Vec<Interfaces>> {
+            let connection = Connection::system().await?;
+            let manager = ObjectManagerProxy::builder(&connection)
+                .destination(UDISKS_NAME)?
+                .path(UDISKS_PATH)?
+                .build()
+                .await?;
+            let objects = manager.get_managed_objects().await?;
+            Ok(objects
+                .into_values()
+                .map(|interfaces| {
+                    interfaces
+                        .into_iter()
+                        .map(|(name, properties)| (name.as_str().to_string(), properties))
+                        .collect()
+                })
+                .collect())
+        }
+        /// Byte strings in UDisks2 are null-terminated
+        fn byte_string(bytes: Vec<u8>) -> String {
+            let bytes = bytes.split(|byte| *byte == 0).next().unwrap_or_default();
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+        fn read_block(block: &HashMap<String, zvariant::OwnedValue>) -> Result<BlockDevice> {
+            Ok(BlockDevice {
+                device: byte_string(get_value(block, "Device")?),
+                size: get_value(block, "Size")?,
+                label: get_value(block, "IdLabel")?,
+                id_type: get_value(block, "IdType")?,
+            })
+        }
+        /// Size and used bytes of the filesystem mounted at `mount_point`
+        fn filesystem_usage(mount_point: &Path) -> Result<(u64, u64)> {
+            let info = File::for_path(mount_point)
+                .query_filesystem_info("filesystem::size,filesystem::used", None::<&Cancellable>)?;
+            Ok((
+                info.attribute_uint64("filesystem::size"),
+                info.attribute_uint64("filesystem::used"),
+            ))
+        }
+
+        /// Every block device: disks, partitions, loop devices, unlocked encrypted volumes...
+        pub async fn list_block_devices() -> Result<Vec<BlockDevice>> {
+            let mut devices = Vec::new();
+            for interfaces in managed_objects().await? {
+                if let Some(block) = interfaces.get(BLOCK_INTERFACE) {
+                    devices.push(read_block(block)?);
+                }
+            }
+            Ok(devices)
+        }
+        /// ## List filesystems
+        /// Block devices holding a filesystem, mounted or not, like the Disks app shows them.
+        /// Usage comes from the mounted filesystem itself, unmounted ones only report their
+        /// size.
+        pub async fn list_filesystems() -> Result<Vec<FilesystemInfo>> {
+            let mut filesystems = Vec::new();
+            for interfaces in managed_objects().await? {
+                let (block, filesystem) = match (
+                    interfaces.get(BLOCK_INTERFACE),
+                    interfaces.get(FILESYSTEM_INTERFACE),
+                ) {
+                    (Some(block), Some(filesystem)) => (block, filesystem),
+                    _ => continue,
+                };
+                let block = read_block(block)?;
+                let mount_points: Vec<Vec<u8>> = get_value(filesystem, "MountPoints")?;
+                let mount_point = mount_points
+                    .into_iter()
+                    .next()
+                    .map(|mount_point| PathBuf::from(byte_string(mount_point)));
+                let (total_bytes, used_bytes) = match &mount_point {
+                    Some(mount_point) => match filesystem_usage(mount_point) {
+                        Ok((total, used)) => (total, Some(used)),
+                        Err(_) => (block.size, None),
+                    },
+                    None => (block.size, None),
+                };
+                filesystems.push(FilesystemInfo {
+                    device: block.device,
+                    label: block.label,
+                    fs_type: block.id_type,
+                    mount_point,
+                    total_bytes,
+                    used_bytes,
+                });
+            }
+            Ok(filesystems)
+        }
+        /// ## Check a device is mounted
+        /// `device` is a device file, e.g. `/dev/sda1` or `/dev/disk/by-uuid/...`.
+        /// Returns `Error::InvalidArgument` when UDisks2 doesn't know the device.
+        pub async fn is_mounted(device: &str) -> Result<bool> {
+            for interfaces in managed_objects().await? {
+                let block = match interfaces.get(BLOCK_INTERFACE) {
+                    Some(block) => block,
+                    None => continue,
+                };
+                let mut names = vec![byte_string(get_value(block, "Device")?)];
+                names.push(byte_string(get_value(block, "PreferredDevice")?));
+                let symlinks: Vec<Vec<u8>> = get_value(block, "Symlinks")?;
+                names.extend(symlinks.into_iter().map(byte_string));
+                if !names.iter().any(|name| name == device) {
+                    continue;
+                }
+                return match interfaces.get(FILESYSTEM_INTERFACE) {
+                    Some(filesystem) => {
+                        let mount_points: Vec<Vec<u8>> = get_value(filesystem, "MountPoints")?;
+                        Ok(!mount_points.is_empty())
+                    }
+                    None => Ok(false),
+                };
+            }
+            Err(Error::InvalidArgument(format!(
+                "No block device {}",
+                device
+            )))
+        }
+    }
+
+    pub mod extensions {
+        use std::collections::HashMap;
+        use std::path::PathBuf;
+        use std::time::Duration;
+
+        use futures_util::{Stream, StreamExt};
+        use gio::glib::{home_dir, Variant};
+        use gio::prelude::*;
+        use gio::SettingsSchemaSource;
+        use serde::Deserialize;
+        use zbus::Connection;
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::ExtensionsProxy;
+        use crate::retry::with_retry;
+        use crate::timeout::timeout;
+        use crate::watch::watch_signal;
+
+        use super::{get_value, service_available, Bus, ListExtension, ListExtensionState};
+
+        /// Whether GNOME Shell runs, e.g. false in other desktops or over SSH
+        pub async fn is_shell_available() -> bool {
+            service_available(Bus::Session, "org.gnome.Shell").await
+        }
+        pub fn set_extensions_active(active: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.shell",
+                "disable-user-extensions",
+                active.to_string().as_str(),
+            )
+        }
+        pub fn get_extensions_active() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.shell", "disable-user-extensions")
+        }
+        pub fn reset_extensions_active() -> Result<()> {
+            crate::dconf::reset("org.gnome.shell", "disable-user-extensions")
+        }
+        /// Flips the setting, returns the new value
+        pub fn toggle_extensions_active() -> Result<bool> {
+            crate::dconf::toggle("org.gnome.shell", "disable-user-extensions")
+        }
+        pub async fn get_extensions() -> Result<Vec<ListExtension>> {
+            let connection = Connection::session().await?;
+            let proxy = ExtensionsProxy::new(&connection).await?;
+            Ok(proxy.list_extensions().await?)
+        }
+        /// Same as `get_extensions`, `Error::Timeout` when the shell doesn't answer within
+        /// `duration`, e.g. while it's frozen
+        pub async fn get_extensions_timeout(duration: Duration) -> Result<Vec<ListExtension>> {
+            timeout(duration, get_extensions()).await?
+        }
+        /// Returns `false` when the shell refused, e.g. for an unknown uuid
+        pub async fn disable_extension(uuid: &str) -> Result<bool> {
+            let connection = Connection::session().await?;
+            let proxy = ExtensionsProxy::new(&connection).await?;
+            Ok(with_retry(|| proxy.DisableExtension(uuid.to_string())).await?)
+        }
+        /// Same as `disable_extension`, `Error::Timeout` when the shell doesn't answer
+        /// within `duration`
+        pub async fn disable_extension_timeout(uuid: &str, duration: Duration) -> Result<bool> {
+            timeout(duration, disable_extension(uuid)).await?
+        }
+        /// Returns `false` when the shell refused, e.g. for an unknown uuid
+        pub async fn enable_extension(uuid: &str) -> Result<bool> {
+            let connection = Connection::session().await?;
+            let proxy = ExtensionsProxy::new(&connection).await?;
+            Ok(with_retry(|| proxy.EnableExtension(uuid.to_string())).await?)
+        }
+        /// Same as `enable_extension`, `Error::Timeout` when the shell doesn't answer
+        /// within `duration`
+        pub async fn enable_extension_timeout(uuid: &str, duration: Duration) -> Result<bool> {
+            timeout(duration, enable_extension(uuid)).await?
+        }
+        /// Returns `false` when the shell refused, e.g. for an unknown uuid
+        pub async fn uninstall_extension(uuid: &str) -> Result<bool> {
+            let connection = Connection::session().await?;
+            let proxy = ExtensionsProxy::new(&connection).await?;
+            Ok(with_retry(|| proxy.UninstallExtension(uuid.to_string())).await?)
+        }
+        /// Same as `uninstall_extension`, `Error::Timeout` when the shell doesn't answer
+        /// within `duration`
+        pub async fn uninstall_extension_timeout(uuid: &str, duration: Duration) -> Result<bool> {
+            timeout(duration, uninstall_extension(uuid)).await?
+        }
+        pub async fn open_extension_preferences(uuid: &str) -> Result<()> {
+            let connection = Connection::session().await?;
+            let proxy = ExtensionsProxy::new(&connection).await?;
+            Ok(proxy.launch_extension_prefs(uuid).await?)
+        }
+        /// Same as `open_extension_preferences`, `Error::Timeout` when the shell doesn't
+        /// answer within `duration`
+        pub async fn open_extension_preferences_timeout(
+            uuid: &str,
+            duration: Duration,
+        ) -> Result<()> {
+            timeout(duration, open_extension_preferences(uuid)).await?
+        }
+        /// GSettings of an extension, from the schema it ships
+        pub struct ExtensionSettings {
+            settings: gio::Settings,
+        }
+        impl ExtensionSettings {
+            /// The bound settings, e.g. to `bind` keys to widgets
+            pub fn gio_settings(&self) -> &gio::Settings {
+                &self.settings
+            }
+            pub fn keys(&self) -> Vec<String> {
+                self.settings
+                    .settings_schema()
+                    .map(|schema| {
+                        schema
+                            .list_keys()
+                            .iter()
+                            .map(|key| key.to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            /// `None` when the schema has no such key
+            pub fn get(&self, key: &str) -> Option<Variant> {
+                self.has_key(key).then(|| self.settings.value(key))
+            }
+            /// Fails when the key is missing, not writable or `value` has another type
+            pub fn set(&self, key: &str, value: &Variant) -> Result<()> {
+                if !self.has_key(key) {
+                    return Err(Error::NotSupported);
+                }
+                self.settings
+                    .set_value(key, value)
+                    .map_err(|error| Error::InvalidArgument(error.message.to_string()))?;
+                gio::Settings::sync();
+                Ok(())
+            }
+            pub fn reset(&self, key: &str) {
+                if self.has_key(key) {
+                    self.settings.reset(key);
+                    gio::Settings::sync();
+                }
+            }
+            fn has_key(&self, key: &str) -> bool {
+                self.settings
+                    .settings_schema()
+                    .is_some_and(|schema| schema.has_key(key))
+            }
+        }
+        /// Directory of an installed extension, user extensions take precedence
+        fn extension_dir(uuid: &str) -> Option<PathBuf> {
+            [
+                home_dir().join(".local/share/gnome-shell/extensions"),
+                PathBuf::from("/usr/share/gnome-shell/extensions"),
+            ]
+            .iter()
+            .map(|dir| dir.join(uuid))
+            .find(|dir| dir.is_dir())
+        }
+        /// Fields of an extension's `metadata.json`
+        #[derive(Debug, Clone, PartialEq, Deserialize)]
+        pub struct ExtensionMetadata {
+            pub uuid: String,
+            pub name: String,
+            #[serde(default)]
+            pub description: String,
+            /// GNOME Shell versions the extension supports, e.g. `["45", "46"]`
+            #[serde(rename = "shell-version", default)]
+            pub shell_version: Vec<String>,
+            pub url: Option<String>,
+            #[serde(rename = "settings-schema")]
+            pub settings_schema: Option<String>,
+            #[serde(rename = "gettext-domain")]
+            pub gettext_domain: Option<String>,
+        }
+        /// ## Read an extension's metadata.json
+        /// Straight from disk, including fields `get_extensions` doesn't have.
+        /// Returns `Error::NotSupported` when the extension isn't installed and `Error::Parse`
+        /// when its metadata is invalid.
+        pub fn read_metadata(uuid: &str) -> Result<ExtensionMetadata> {
+            let extension_dir = extension_dir(uuid).ok_or(Error::NotSupported)?;
+            let metadata = std::fs::read_to_string(extension_dir.join("metadata.json"))?;
+            serde_json::from_str(&metadata).map_err(|error| Error::Parse(error.to_string()))
+        }
+        /// ## Settings of an extension
+        /// Loads the schema from the extension's `schemas` directory, in the user or system
+        /// extensions directory, falling back to the schemas installed system wide for the
+        /// `settings-schema` of its metadata. Returns `Error::NotSupported` for extensions
+        /// without settings.
+        pub fn settings(uuid: &str) -> Result<ExtensionSettings> {
+            let extension_dir = extension_dir(uuid).ok_or(Error::NotSupported)?;
+
+            let default_source = SettingsSchemaSource::default();
+            let schemas_dir = extension_dir.join("schemas");
+            let compiled = schemas_dir.join("gschemas.compiled").exists();
+            let source = if compiled {
+                SettingsSchemaSource::from_directory(&schemas_dir, default_source.as_ref(), false)?
+            } else {
+                default_source.ok_or(Error::NotSupported)?
+            };
+            let metadata_schema_id = read_metadata(uuid)
+                .ok()
+                .and_then(|metadata| metadata.settings_schema);
+            let schema_id = match metadata_schema_id {
+                Some(id) => id,
+                // Extensions without `settings-schema` in their metadata ship a single schema,
+                // only looked up in their own directory: the system schemas belong to others
+                None if compiled => {
+                    let (non_relocatable, _) = source.list_schemas(false);
+                    non_relocatable
+                        .first()
+                        .map(|id| id.to_string())
+                        .ok_or(Error::NotSupported)?
+                }
+                None => return Err(Error::NotSupported),
+            };
+            let schema = source.lookup(&schema_id, true).ok_or(Error::NotSupported)?;
+            Ok(ExtensionSettings {
+                settings: gio::Settings::new_full(&schema, None::<&gio::SettingsBackend>, None),
+            })
+        }
+        /// Emits the uuid and new state of an extension every time one is enabled,
+        /// disabled, installed or uninstalled
+        pub async fn watch_extension_state(
+        ) -> Result<impl Stream<Item = (String, ListExtensionState)>> {
+            let connection = Connection::session().await?;
+            let proxy = ExtensionsProxy::new(&connection).await?;
+            let changes = watch_signal::<(String, HashMap<String, zvariant::OwnedValue>)>(
+                &proxy,
+                "ExtensionStateChanged",
+            )
+            .await?;
+            Ok(changes.filter_map(|(uuid, extension)| async move {
+                let state: f64 = get_value(&extension, "state").ok()?;
+                Some((uuid, ListExtensionState::from(state)))
+            }))
+        }
+    }
+
+    pub mod interface {
+        use std::collections::BTreeSet;
+        use std::path::{Path, PathBuf};
+
+        use gio::glib::home_dir;
+        use serde::{Deserialize, Serialize};
+
+        use crate::error::{Error, Result};
+
+        /// Names of the directories inside `dirs` for which `is_theme` returns true, deduplicated and sorted
+        fn list_theme_dirs(dirs: &[PathBuf], is_theme: impl Fn(&Path) -> bool) -> Vec<String> {
+            let mut themes: BTreeSet<String> = BTreeSet::new();
+            for dir in dirs {
+                let entries = match std::fs::read_dir(dir) {
+                    Ok(entries) => entries,
+                    Err(_) => continue,
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() && is_theme(&path) {
+                        themes.insert(entry.file_name().to_string_lossy().to_string());
+                    }
+                }
+            }
+            themes.into_iter().collect()
+        }
+        fn icon_theme_dirs() -> Vec<PathBuf> {
+            vec![
+                home_dir().join(".icons"),
+                home_dir().join(".local/share/icons"),
+                PathBuf::from("/usr/share/icons"),
+            ]
+        }
+        fn theme_dirs() -> Vec<PathBuf> {
+            vec![
+                home_dir().join(".themes"),
+                home_dir().join(".local/share/themes"),
+                PathBuf::from("/usr/share/themes"),
+            ]
+        }
+
+        pub fn set_show_battery_percentage(show: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "show-battery-percentage",
+                show.to_string().as_str(),
+            )
+        }
+        pub fn get_show_battery_percentage() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "show-battery-percentage")
+        }
+        pub fn reset_show_battery_percentage() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "show-battery-percentage")
+        }
+        /// Flips the setting, returns the new value
+        pub fn toggle_show_battery_percentage() -> Result<bool> {
+            crate::dconf::toggle("org.gnome.desktop.interface", "show-battery-percentage")
+        }
+        pub fn set_locate_pointer(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "locate-pointer",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_locate_pointer() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "locate-pointer")
+        }
+        pub fn reset_locate_pointer() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "locate-pointer")
+        }
+        /// Flips the setting, returns the new value
+        pub fn toggle_locate_pointer() -> Result<bool> {
+            crate::dconf::toggle("org.gnome.desktop.interface", "locate-pointer")
+        }
+        pub fn set_cursor_size(size: u32) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "cursor-size",
+                size.to_string().as_str(),
+            )
+        }
+        pub fn get_cursor_size() -> Result<u32> {
+            let value = crate::dconf::get("org.gnome.desktop.interface", "cursor-size")?;
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::Parse("Unable to read cursor-size".to_string()))
+        }
+        pub fn reset_cursor_size() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "cursor-size")
+        }
+
+        #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+        pub enum ClockFormat {
+            TwelveHours,
+            TwentyFourHours,
+        }
+        impl ClockFormat {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    ClockFormat::TwelveHours => "12h",
+                    ClockFormat::TwentyFourHours => "24h",
+                }
+            }
+            fn from(format: &str) -> ClockFormat {
+                match format {
+                    "12h" => ClockFormat::TwelveHours,
+                    _ => ClockFormat::TwentyFourHours,
+                }
+            }
+        }
+        pub fn set_clock_format(format: ClockFormat) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "clock-format",
+                crate::dconf::format_string(format.as_str()).as_str(),
+            )
+        }
+        pub fn get_clock_format() -> Result<ClockFormat> {
+            let value = crate::dconf::get_raw("org.gnome.desktop.interface", "clock-format")?;
+            Ok(ClockFormat::from(&crate::dconf::parse_string(&value)))
+        }
+        pub fn reset_clock_format() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "clock-format")
+        }
+        pub fn set_clock_show_seconds(show: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "clock-show-seconds",
+                show.to_string().as_str(),
+            )
+        }
+        pub fn get_clock_show_seconds() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "clock-show-seconds")
+        }
+        pub fn reset_clock_show_seconds() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "clock-show-seconds")
+        }
+        pub fn set_clock_show_weekday(show: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "clock-show-weekday",
+                show.to_string().as_str(),
+            )
+        }
+        pub fn get_clock_show_weekday() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "clock-show-weekday")
+        }
+        pub fn reset_clock_show_weekday() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "clock-show-weekday")
+        }
+        pub fn set_clock_show_date(show: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "clock-show-date",
+                show.to_string().as_str(),
+            )
+        }
+        pub fn get_clock_show_date() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "clock-show-date")
+        }
+        pub fn reset_clock_show_date() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "clock-show-date")
+        }
+        #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+        pub enum ColorScheme {
+            Default,
+            PreferDark,
+            PreferLight,
+        }
+        impl ColorScheme {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    ColorScheme::Default => "default",
+                    ColorScheme::PreferDark => "prefer-dark",
+                    ColorScheme::PreferLight => "prefer-light",
+                }
+            }
+            fn from(scheme: &str) -> ColorScheme {
+                match scheme {
+                    "prefer-dark" => ColorScheme::PreferDark,
+                    "prefer-light" => ColorScheme::PreferLight,
+                    _ => ColorScheme::Default,
+                }
+            }
+        }
+        pub fn set_color_scheme(scheme: ColorScheme) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "color-scheme",
+                crate::dconf::format_string(scheme.as_str()).as_str(),
+            )
+        }
+        pub fn get_color_scheme() -> Result<ColorScheme> {
+            let value = crate::dconf::get_string("org.gnome.desktop.interface", "color-scheme")?;
+            Ok(ColorScheme::from(&value))
+        }
+        pub fn reset_color_scheme() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "color-scheme")
+        }
+        /// Accent color picked in the Appearance panel, available since GNOME 47
+        #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+        pub enum AccentColor {
+            Blue,
+            Teal,
+            Green,
+            Yellow,
+            Orange,
+            Red,
+            Pink,
+            Purple,
+            Slate,
+        }
+        impl AccentColor {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    AccentColor::Blue => "blue",
+                    AccentColor::Teal => "teal",
+                    AccentColor::Green => "green",
+                    AccentColor::Yellow => "yellow",
+                    AccentColor::Orange => "orange",
+                    AccentColor::Red => "red",
+                    AccentColor::Pink => "pink",
+                    AccentColor::Purple => "purple",
+                    AccentColor::Slate => "slate",
+                }
+            }
+            fn from(color: &str) -> AccentColor {
+                match color {
+                    "teal" => AccentColor::Teal,
+                    "green" => AccentColor::Green,
+                    "yellow" => AccentColor::Yellow,
+                    "orange" => AccentColor::Orange,
+                    "red" => AccentColor::Red,
+                    "pink" => AccentColor::Pink,
+                    "purple" => AccentColor::Purple,
+                    "slate" => AccentColor::Slate,
+                    _ => AccentColor::Blue,
+                }
+            }
+            /// RGB value of the accent, as defined by libadwaita's `adw_accent_color_to_rgba`
+            pub fn rgb(&self) -> Color {
+                let (red, green, blue) = match self {
+                    AccentColor::Blue => (0x35, 0x84, 0xe4),
+                    AccentColor::Teal => (0x21, 0x90, 0xa4),
+                    AccentColor::Green => (0x3a, 0x94, 0x4a),
+                    AccentColor::Yellow => (0xc8, 0x88, 0x00),
+                    AccentColor::Orange => (0xed, 0x5b, 0x00),
+                    AccentColor::Red => (0xe6, 0x2d, 0x42),
+                    AccentColor::Pink => (0xd5, 0x61, 0x99),
+                    AccentColor::Purple => (0x91, 0x41, 0xac),
+                    AccentColor::Slate => (0x6f, 0x83, 0x96),
+                };
+                Color { red, green, blue }
+            }
+        }
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub struct Color {
+            pub red: u8,
+            pub green: u8,
+            pub blue: u8,
+        }
+        impl Color {
+            /// CSS hex notation, e.g. `#3584e4`
+            pub fn to_hex(&self) -> String {
+                format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+            }
+        }
+        pub fn set_accent_color(color: AccentColor) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "accent-color",
+                crate::dconf::format_string(color.as_str()).as_str(),
+            )
+        }
+        pub fn get_accent_color() -> Result<AccentColor> {
+            let value = crate::dconf::get_string("org.gnome.desktop.interface", "accent-color")?;
+            Ok(AccentColor::from(&value))
+        }
+        pub fn reset_accent_color() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "accent-color")
+        }
+        /// RGB value of the current accent color, to match it in an app's own UI
+        pub fn accent_color_rgb() -> Result<Color> {
+            Ok(get_accent_color()?.rgb())
+        }
+        pub fn set_animations_enabled(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "enable-animations",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_animations_enabled() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "enable-animations")
+        }
+        pub fn reset_animations_enabled() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "enable-animations")
+        }
+        /// GNOME Settings' "Reduce Animation" accessibility switch is backed by
+        /// `enable-animations` too, so reduced motion is just its inverse.
+        pub fn set_reduced_motion(reduced: bool) -> Result<()> {
+            set_animations_enabled(!reduced)
+        }
+        pub fn get_reduced_motion() -> Result<bool> {
+            Ok(!get_animations_enabled()?)
+        }
+        pub fn set_icon_theme(theme: &str) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "icon-theme",
+                crate::dconf::format_string(theme).as_str(),
+            )
+        }
+        pub fn get_icon_theme() -> Result<String> {
+            crate::dconf::get_string("org.gnome.desktop.interface", "icon-theme")
+        }
+        pub fn reset_icon_theme() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "icon-theme")
+        }
+        pub fn set_cursor_theme(theme: &str) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "cursor-theme",
+                crate::dconf::format_string(theme).as_str(),
+            )
+        }
+        pub fn get_cursor_theme() -> Result<String> {
+            crate::dconf::get_string("org.gnome.desktop.interface", "cursor-theme")
+        }
+        pub fn reset_cursor_theme() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "cursor-theme")
+        }
+        pub fn set_gtk_theme(theme: &str) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "gtk-theme",
+                crate::dconf::format_string(theme).as_str(),
+            )
+        }
+        pub fn get_gtk_theme() -> Result<String> {
+            crate::dconf::get_string("org.gnome.desktop.interface", "gtk-theme")
+        }
+        pub fn reset_gtk_theme() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "gtk-theme")
+        }
+        /// Appearance settings switched together, e.g. when going from day to night
+        #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+        pub struct Preset {
+            pub color_scheme: ColorScheme,
+            pub gtk_theme: String,
+            pub icon_theme: String,
+        }
+        /// ## Apply a theme preset
+        /// Every key is applied even when some fail, the keys that failed are returned.
+        pub fn apply_preset(preset: &Preset) -> std::result::Result<(), Vec<String>> {
+            let results = [
+                ("color-scheme", set_color_scheme(preset.color_scheme)),
+                ("gtk-theme", set_gtk_theme(&preset.gtk_theme)),
+                ("icon-theme", set_icon_theme(&preset.icon_theme)),
+            ];
+            let failed: Vec<String> = results
+                .iter()
+                .filter(|(_, result)| result.is_err())
+                .map(|(key, _)| key.to_string())
+                .collect();
+            if failed.is_empty() {
+                Ok(())
+            } else {
+                Err(failed)
+            }
+        }
+        /// Dark style with Adwaita, like the Appearance panel's "Dark" option
+        pub fn apply_dark_preset() -> std::result::Result<(), Vec<String>> {
+            apply_preset(&Preset {
+                color_scheme: ColorScheme::PreferDark,
+                gtk_theme: "Adwaita-dark".to_string(),
+                icon_theme: "Adwaita".to_string(),
+            })
+        }
+        /// Default style with Adwaita, like the Appearance panel's "Default" option
+        pub fn apply_light_preset() -> std::result::Result<(), Vec<String>> {
+            apply_preset(&Preset {
+                color_scheme: ColorScheme::Default,
+                gtk_theme: "Adwaita".to_string(),
+                icon_theme: "Adwaita".to_string(),
+            })
+        }
+        /// Icon themes found in `~/.icons`, `~/.local/share/icons` and `/usr/share/icons`.
+        /// Cursor only themes are skipped, their `index.theme` lists no icon directories.
+        pub fn list_installed_icon_themes() -> Vec<String> {
+            list_theme_dirs(&icon_theme_dirs(), |path| {
+                match std::fs::read_to_string(path.join("index.theme")) {
+                    Ok(index) => index.lines().any(|line| line.starts_with("Directories=")),
+                    Err(_) => false,
+                }
+            })
+        }
+        /// Cursor themes found in the same directories as the icon themes
+        pub fn list_installed_cursor_themes() -> Vec<String> {
+            list_theme_dirs(&icon_theme_dirs(), |path| path.join("cursors").is_dir())
+        }
+        /// GTK themes (with a `gtk-3.0` or `gtk-4.0` directory) found in `~/.themes`,
+        /// `~/.local/share/themes` and `/usr/share/themes`
+        pub fn list_gtk_themes() -> Vec<String> {
+            list_theme_dirs(&theme_dirs(), |path| {
+                path.join("gtk-3.0").is_dir() || path.join("gtk-4.0").is_dir()
+            })
+        }
+        /// GNOME Shell themes (with a `gnome-shell` directory) found in the same directories
+        /// as the GTK themes. Applying them needs the User Themes extension.
+        pub fn list_shell_themes() -> Vec<String> {
+            list_theme_dirs(&theme_dirs(), |path| path.join("gnome-shell").is_dir())
+        }
+        pub fn set_hot_corners_enabled(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "enable-hot-corners",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_hot_corners_enabled() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.interface", "enable-hot-corners")
+        }
+        pub fn reset_hot_corners_enabled() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "enable-hot-corners")
+        }
+        /// Maximize or tile windows when they are dropped on the screen edges
+        pub fn set_edge_tiling(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.mutter",
+                "edge-tiling",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_edge_tiling() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.mutter", "edge-tiling")
+        }
+        pub fn reset_edge_tiling() -> Result<()> {
+            crate::dconf::reset("org.gnome.mutter", "edge-tiling")
+        }
+        pub fn set_dynamic_workspaces(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.mutter",
+                "dynamic-workspaces",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_dynamic_workspaces() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.mutter", "dynamic-workspaces")
+        }
+        pub fn reset_dynamic_workspaces() -> Result<()> {
+            crate::dconf::reset("org.gnome.mutter", "dynamic-workspaces")
+        }
+
+        #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+        pub enum Button {
+            AppMenu,
+            Menu,
+            Icon,
+            Minimize,
+            Maximize,
+            Close,
+            /// Blank space, shown as wide as a button
+            Spacer,
+        }
+        impl Button {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    Button::AppMenu => "appmenu",
+                    Button::Menu => "menu",
+                    Button::Icon => "icon",
+                    Button::Minimize => "minimize",
+                    Button::Maximize => "maximize",
+                    Button::Close => "close",
+                    Button::Spacer => "spacer",
+                }
+            }
+            fn from(button: &str) -> Option<Button> {
+                match button {
+                    "appmenu" => Some(Button::AppMenu),
+                    "menu" => Some(Button::Menu),
+                    "icon" => Some(Button::Icon),
+                    "minimize" => Some(Button::Minimize),
+                    "maximize" => Some(Button::Maximize),
+                    "close" => Some(Button::Close),
+                    "spacer" => Some(Button::Spacer),
+                    _ => None,
+                }
+            }
+        }
+        /// Buttons of the window titlebars, on each side of the title
+        #[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+        pub struct ButtonLayout {
+            pub left: Vec<Button>,
+            pub right: Vec<Button>,
+        }
+        impl ButtonLayout {
+            /// Parses a `button-layout` value like `appmenu:minimize,maximize,close`.
+            /// Unknown button names are skipped.
+            pub fn parse(layout: &str) -> ButtonLayout {
+                let parse_side = |side: &str| -> Vec<Button> {
+                    side.split(',')
+                        .filter_map(|button| Button::from(button.trim()))
+                        .collect()
+                };
+                let (left, right) = layout.split_once(':').unwrap_or((layout, ""));
+                ButtonLayout {
+                    left: parse_side(left),
+                    right: parse_side(right),
+                }
+            }
+            /// Adds `button` at the end of the right side, or removes it from both sides
+            pub fn set_button(&mut self, button: Button, shown: bool) {
+                self.left.retain(|b| *b != button);
+                self.right.retain(|b| *b != button);
+                if shown {
+                    self.right.push(button);
+                }
+            }
+        }
+        impl std::fmt::Display for ButtonLayout {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let join = |buttons: &[Button]| -> String {
+                    buttons
+                        .iter()
+                        .map(|button| button.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(",")
+                };
+                write!(f, "{}:{}", join(&self.left), join(&self.right))
+            }
+        }
+        pub fn set_window_buttons_layout(layout: &ButtonLayout) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.wm.preferences",
+                "button-layout",
+                crate::dconf::format_string(&layout.to_string()).as_str(),
+            )
+        }
+        pub fn get_window_buttons_layout() -> Result<ButtonLayout> {
+            let value =
+                crate::dconf::get_string("org.gnome.desktop.wm.preferences", "button-layout")?;
+            Ok(ButtonLayout::parse(&value))
+        }
+        pub fn reset_window_buttons_layout() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.wm.preferences", "button-layout")
+        }
+        /// ## Restore the default interface settings
+        /// Resets every key of `org.gnome.desktop.interface`, including the ones this module
+        /// has no function for, along with the window manager keys of this module.
+        pub fn reset_all() -> Result<()> {
+            crate::dconf::reset_all("org.gnome.desktop.interface")?;
+            reset_edge_tiling()?;
+            reset_dynamic_workspaces()?;
+            reset_window_buttons_layout()
+        }
+    }
+
+    /// # Workspaces
+    /// Settings from the Multitasking panel of GNOME Settings.
+    pub mod workspaces {
+        use crate::error::{Error, Result};
+
+        const MUTTER_SCHEMA: &str = "org.gnome.mutter";
+        const WM_PREFERENCES_SCHEMA: &str = "org.gnome.desktop.wm.preferences";
+
+        /// Number of workspaces, only used when dynamic workspaces are off
+        pub fn set_count(count: u32) -> Result<()> {
+            crate::dconf::set(
+                WM_PREFERENCES_SCHEMA,
+                "num-workspaces",
+                count.to_string().as_str(),
+            )
+        }
+        pub fn get_count() -> Result<u32> {
+            let value = crate::dconf::get(WM_PREFERENCES_SCHEMA, "num-workspaces")?;
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::Parse("Unable to read num-workspaces".to_string()))
+        }
+        pub fn reset_count() -> Result<()> {
+            crate::dconf::reset(WM_PREFERENCES_SCHEMA, "num-workspaces")
+        }
+        /// Add and remove workspaces as needed instead of keeping `get_count` of them
+        pub fn set_dynamic(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                MUTTER_SCHEMA,
+                "dynamic-workspaces",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_dynamic() -> Result<bool> {
+            crate::dconf::get_bool(MUTTER_SCHEMA, "dynamic-workspaces")
+        }
+        pub fn reset_dynamic() -> Result<()> {
+            crate::dconf::reset(MUTTER_SCHEMA, "dynamic-workspaces")
+        }
+        /// Switch workspaces on the primary display only, the other displays stay put
+        pub fn set_workspaces_only_on_primary(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                MUTTER_SCHEMA,
+                "workspaces-only-on-primary",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_workspaces_only_on_primary() -> Result<bool> {
+            crate::dconf::get_bool(MUTTER_SCHEMA, "workspaces-only-on-primary")
+        }
+        pub fn reset_workspaces_only_on_primary() -> Result<()> {
+            crate::dconf::reset(MUTTER_SCHEMA, "workspaces-only-on-primary")
+        }
+    }
+
+    /// # Window manager
+    /// Window behavior settings from `org.gnome.desktop.wm.preferences`.
+    pub mod wm {
+        use serde::{Deserialize, Serialize};
+
+        use crate::error::Result;
+
+        const MUTTER_SCHEMA: &str = "org.gnome.mutter";
+        const WM_PREFERENCES_SCHEMA: &str = "org.gnome.desktop.wm.preferences";
+
+        #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+        pub enum FocusMode {
+            /// Windows are focused when clicked
+            Click,
+            /// Windows are focused when hovered and stay focused when the pointer leaves
+            /// to the desktop
+            Sloppy,
+            /// Windows are focused when hovered and unfocused when the pointer leaves them
+            Mouse,
+        }
+        impl FocusMode {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    FocusMode::Click => "click",
+                    FocusMode::Sloppy => "sloppy",
+                    FocusMode::Mouse => "mouse",
+                }
+            }
+            fn from(mode: &str) -> FocusMode {
+                match mode {
+                    "sloppy" => FocusMode::Sloppy,
+                    "mouse" => FocusMode::Mouse,
+                    _ => FocusMode::Click,
+                }
+            }
+        }
+        pub fn set_focus_mode(mode: FocusMode) -> Result<()> {
+            crate::dconf::set(
+                WM_PREFERENCES_SCHEMA,
+                "focus-mode",
+                crate::dconf::format_string(mode.as_str()).as_str(),
+            )
+        }
+        pub fn get_focus_mode() -> Result<FocusMode> {
+            let value = crate::dconf::get_string(WM_PREFERENCES_SCHEMA, "focus-mode")?;
+            Ok(FocusMode::from(&value))
+        }
+        pub fn reset_focus_mode() -> Result<()> {
+            crate::dconf::reset(WM_PREFERENCES_SCHEMA, "focus-mode")
+        }
+        /// Raise windows when they get focused by hovering them, only used with the sloppy
+        /// and mouse focus modes
+        pub fn set_auto_raise(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                WM_PREFERENCES_SCHEMA,
+                "auto-raise",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_auto_raise() -> Result<bool> {
+            crate::dconf::get_bool(WM_PREFERENCES_SCHEMA, "auto-raise")
+        }
+        pub fn reset_auto_raise() -> Result<()> {
+            crate::dconf::reset(WM_PREFERENCES_SCHEMA, "auto-raise")
+        }
+        /// Raise windows when their content is clicked, not only their titlebar
+        pub fn set_raise_on_click(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                WM_PREFERENCES_SCHEMA,
+                "raise-on-click",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_raise_on_click() -> Result<bool> {
+            crate::dconf::get_bool(WM_PREFERENCES_SCHEMA, "raise-on-click")
+        }
+        pub fn reset_raise_on_click() -> Result<()> {
+            crate::dconf::reset(WM_PREFERENCES_SCHEMA, "raise-on-click")
+        }
+        /// Attach dialogs to their parent window, they move with it and can't be moved
+        /// on their own
+        pub fn set_attach_modal_dialogs(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                MUTTER_SCHEMA,
+                "attach-modal-dialogs",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_attach_modal_dialogs() -> Result<bool> {
+            crate::dconf::get_bool(MUTTER_SCHEMA, "attach-modal-dialogs")
+        }
+        pub fn reset_attach_modal_dialogs() -> Result<()> {
+            crate::dconf::reset(MUTTER_SCHEMA, "attach-modal-dialogs")
+        }
+        /// Open new windows in the center of the screen
+        pub fn set_center_new_windows(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                MUTTER_SCHEMA,
+                "center-new-windows",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_center_new_windows() -> Result<bool> {
+            crate::dconf::get_bool(MUTTER_SCHEMA, "center-new-windows")
+        }
+        pub fn reset_center_new_windows() -> Result<()> {
+            crate::dconf::reset(MUTTER_SCHEMA, "center-new-windows")
+        }
+        /// Resize windows with the right button while holding the modifier key, the middle
+        /// button then opens the window menu
+        pub fn set_resize_with_right_button(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                WM_PREFERENCES_SCHEMA,
+                "resize-with-right-button",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_resize_with_right_button() -> Result<bool> {
+            crate::dconf::get_bool(WM_PREFERENCES_SCHEMA, "resize-with-right-button")
+        }
+        pub fn reset_resize_with_right_button() -> Result<()> {
+            crate::dconf::reset(WM_PREFERENCES_SCHEMA, "resize-with-right-button")
+        }
+        /// Key held to move and resize windows by clicking anywhere in them, e.g. `<Super>`
+        /// or `<Alt>`, an empty string disables it
+        pub fn set_mouse_button_modifier(modifier: &str) -> Result<()> {
+            crate::dconf::set(
+                WM_PREFERENCES_SCHEMA,
+                "mouse-button-modifier",
+                crate::dconf::format_string(modifier).as_str(),
+            )
+        }
+        pub fn get_mouse_button_modifier() -> Result<String> {
+            crate::dconf::get_string(WM_PREFERENCES_SCHEMA, "mouse-button-modifier")
+        }
+        pub fn reset_mouse_button_modifier() -> Result<()> {
+            crate::dconf::reset(WM_PREFERENCES_SCHEMA, "mouse-button-modifier")
+        }
+    }
+
+    /// # Privacy
+    /// Settings from the Privacy panel of GNOME Settings.
+    pub mod privacy {
+        use crate::error::{Error, Result};
+
+        const PRIVACY_SCHEMA: &str = "org.gnome.desktop.privacy";
+
+        pub fn set_remember_recent_files(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                PRIVACY_SCHEMA,
+                "remember-recent-files",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_remember_recent_files() -> Result<bool> {
+            crate::dconf::get_bool(PRIVACY_SCHEMA, "remember-recent-files")
+        }
+        pub fn reset_remember_recent_files() -> Result<()> {
+            crate::dconf::reset(PRIVACY_SCHEMA, "remember-recent-files")
+        }
+        /// Automatically delete trash content older than `get_old_files_age` days
+        pub fn set_remove_old_trash_files(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                PRIVACY_SCHEMA,
+                "remove-old-trash-files",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_remove_old_trash_files() -> Result<bool> {
+            crate::dconf::get_bool(PRIVACY_SCHEMA, "remove-old-trash-files")
+        }
+        pub fn reset_remove_old_trash_files() -> Result<()> {
+            crate::dconf::reset(PRIVACY_SCHEMA, "remove-old-trash-files")
+        }
+        /// Age in days after which old trash and temporary files are removed
+        pub fn set_old_files_age(days: u32) -> Result<()> {
+            crate::dconf::set(PRIVACY_SCHEMA, "old-files-age", days.to_string().as_str())
+        }
+        pub fn get_old_files_age() -> Result<u32> {
+            let value = crate::dconf::get(PRIVACY_SCHEMA, "old-files-age")?;
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::Parse("Unable to read old-files-age".to_string()))
+        }
+        pub fn reset_old_files_age() -> Result<()> {
+            crate::dconf::reset(PRIVACY_SCHEMA, "old-files-age")
+        }
+        /// Send crash reports to the distribution, when it supports it
+        pub fn set_report_technical_problems(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                PRIVACY_SCHEMA,
+                "report-technical-problems",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_report_technical_problems() -> Result<bool> {
+            crate::dconf::get_bool(PRIVACY_SCHEMA, "report-technical-problems")
+        }
+        pub fn reset_report_technical_problems() -> Result<()> {
+            crate::dconf::reset(PRIVACY_SCHEMA, "report-technical-problems")
+        }
+        /// Whether apps may use the camera, the inverse of the `disable-camera` key
+        pub fn set_camera_enabled(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                PRIVACY_SCHEMA,
+                "disable-camera",
+                (!enabled).to_string().as_str(),
+            )
+        }
+        pub fn get_camera_enabled() -> Result<bool> {
+            Ok(!crate::dconf::get_bool(PRIVACY_SCHEMA, "disable-camera")?)
+        }
+        pub fn reset_camera_enabled() -> Result<()> {
+            crate::dconf::reset(PRIVACY_SCHEMA, "disable-camera")
+        }
+        /// Whether apps may use the microphone, the inverse of the `disable-microphone` key
+        pub fn set_microphone_enabled(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                PRIVACY_SCHEMA,
+                "disable-microphone",
+                (!enabled).to_string().as_str(),
+            )
+        }
+        pub fn get_microphone_enabled() -> Result<bool> {
+            Ok(!crate::dconf::get_bool(
+                PRIVACY_SCHEMA,
+                "disable-microphone",
+            )?)
+        }
+        pub fn reset_microphone_enabled() -> Result<()> {
+            crate::dconf::reset(PRIVACY_SCHEMA, "disable-microphone")
+        }
+    }
 
-            let mut devices_battery: Vec<DeviceProxy<'_>> = Vec::new();
-            for device in devices {
-                let device_proxy = DeviceProxy::new(&connection, device).await?;
-                let is_rechargable = device_proxy.is_rechargeable().await?;
-                if is_rechargable {
-                    devices_battery.push(device_proxy);
-                }
+    /// # Trash
+    /// The user's trash, across every mounted volume, through the `trash://` GVfs backend.
+    pub mod trash {
+        use std::path::Path;
+
+        use gio::prelude::*;
+        use gio::{Cancellable, File, FileQueryInfoFlags, FileType};
+
+        use crate::error::Result;
+
+        const TRASH_URI: &str = "trash:///";
+
+        /// Moves a file or directory to the trash
+        pub fn trash_item(path: &Path) -> Result<()> {
+            File::for_path(path).trash(None::<&Cancellable>)?;
+            Ok(())
+        }
+        /// Permanently deletes everything in the trash
+        pub fn empty_trash() -> Result<()> {
+            let trash = File::for_uri(TRASH_URI);
+            let children = trash.enumerate_children(
+                "standard::name",
+                FileQueryInfoFlags::NOFOLLOW_SYMLINKS,
+                None::<&Cancellable>,
+            )?;
+            // The trash backend deletes top level directories along with their content
+            while let Some(info) = children.next_file(None::<&Cancellable>)? {
+                children.child(&info).delete(None::<&Cancellable>)?;
             }
-            Ok(devices_battery)
+            Ok(())
+        }
+        /// Returns the number of items in the trash and the size in bytes of their content
+        pub fn trash_size() -> Result<(u64, u64)> {
+            let trash = File::for_uri(TRASH_URI);
+            let children = trash.enumerate_children(
+                "standard::name,standard::type,standard::size",
+                FileQueryInfoFlags::NOFOLLOW_SYMLINKS,
+                None::<&Cancellable>,
+            )?;
+            let mut count = 0;
+            let mut bytes = 0;
+            while let Some(info) = children.next_file(None::<&Cancellable>)? {
+                count += 1;
+                bytes += info_size(&children.child(&info), &info)?;
+            }
+            Ok((count, bytes))
+        }
+        fn info_size(file: &File, info: &gio::FileInfo) -> Result<u64> {
+            if info.file_type() != FileType::Directory {
+                return Ok(info.size().max(0) as u64);
+            }
+            let children = file.enumerate_children(
+                "standard::name,standard::type,standard::size",
+                FileQueryInfoFlags::NOFOLLOW_SYMLINKS,
+                None::<&Cancellable>,
+            )?;
+            let mut bytes = 0;
+            while let Some(info) = children.next_file(None::<&Cancellable>)? {
+                bytes += info_size(&children.child(&info), &info)?;
+            }
+            Ok(bytes)
         }
     }
 
-    pub mod extensions {
-        use zbus::Connection;
-
-        use crate::handlers::easy_gnome::ExtensionsProxy;
+    /// # Accessibility
+    /// Accessibility settings spread across the `org.gnome.desktop.a11y.*` and
+    /// `org.gnome.desktop.interface` schemas.
+    pub mod accessibility {
+        use crate::error::{Error, Result};
 
-        use super::ListExtension;
+        /// Scaling factor used by GNOME Settings' "Large Text" switch
+        pub const LARGE_TEXT_SCALING_FACTOR: f64 = 1.25;
 
-        pub fn set_extensions_active(active: bool) {
+        pub fn set_high_contrast(enabled: bool) -> Result<()> {
             crate::dconf::set(
-                "org.gnome.shell",
-                "disable-user-extensions",
-                active.to_string().as_str(),
+                "org.gnome.desktop.a11y.interface",
+                "high-contrast",
+                enabled.to_string().as_str(),
             )
-            .unwrap();
         }
-        pub fn get_extensions_active() -> bool {
-            let value = crate::dconf::get("org.gnome.shell", "disable-user-extensions").unwrap();
-            value.parse::<bool>().unwrap()
+        pub fn get_high_contrast() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.a11y.interface", "high-contrast")
         }
-        pub fn reset_extensions_active() {
-            crate::dconf::reset("org.gnome.shell", "disable-user-extensions").unwrap();
+        pub fn reset_high_contrast() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.a11y.interface", "high-contrast")
+        }
+        pub fn set_text_scaling_factor(factor: f64) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.interface",
+                "text-scaling-factor",
+                factor.to_string().as_str(),
+            )
         }
-        pub async fn get_extensions() -> Vec<ListExtension> {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ExtensionsProxy::new(&connection).await.unwrap();
-            proxy.list_extensions().await
+        pub fn get_text_scaling_factor() -> Result<f64> {
+            let value = crate::dconf::get("org.gnome.desktop.interface", "text-scaling-factor")?;
+            value
+                .parse::<f64>()
+                .map_err(|_| Error::Parse("Unable to read text-scaling-factor".to_string()))
         }
-        pub async fn disable_extension(uuid: &str) {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ExtensionsProxy::new(&connection).await.unwrap();
-            proxy.DisableExtension(uuid.to_string()).await.unwrap();
+        pub fn reset_text_scaling_factor() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.interface", "text-scaling-factor")
         }
-        pub async fn enable_extension(uuid: &str) {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ExtensionsProxy::new(&connection).await.unwrap();
-            proxy.EnableExtension(uuid.to_string()).await.unwrap();
+        /// Large text is a text scaling factor of `LARGE_TEXT_SCALING_FACTOR`, disabling it resets the factor
+        pub fn set_large_text(enabled: bool) -> Result<()> {
+            if enabled {
+                set_text_scaling_factor(LARGE_TEXT_SCALING_FACTOR)
+            } else {
+                reset_text_scaling_factor()
+            }
         }
-        pub async fn uninstall_extension(uuid: &str) {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ExtensionsProxy::new(&connection).await.unwrap();
-            proxy.UninstallExtension(uuid.to_string()).await.unwrap();
+        /// Whether the text is scaled up, by the large text switch or any bigger factor
+        pub fn get_large_text() -> Result<bool> {
+            Ok(get_text_scaling_factor()? > 1.0)
         }
-        pub async fn open_extension_preferences(uuid: &str) {
-            let connection = Connection::session().await.unwrap();
-            let proxy = ExtensionsProxy::new(&connection).await.unwrap();
-            proxy.launch_extension_prefs(uuid).await.unwrap();
+        pub fn reset_large_text() -> Result<()> {
+            reset_text_scaling_factor()
         }
-    }
-
-    pub mod interface {
-        pub fn set_show_battery_percentage(show: bool) -> Result<(), String> {
+        pub fn set_screen_reader_enabled(enabled: bool) -> Result<()> {
             crate::dconf::set(
-                "org.gnome.desktop.interface",
-                "show-battery-percentage",
-                show.to_string().as_str(),
+                "org.gnome.desktop.a11y.applications",
+                "screen-reader-enabled",
+                enabled.to_string().as_str(),
             )
         }
-        pub fn get_show_battery_percentage() -> Result<bool, String> {
-            let value =
-                crate::dconf::get("org.gnome.desktop.interface", "show-battery-percentage")?;
-            Ok(value.parse::<bool>().unwrap())
+        pub fn get_screen_reader_enabled() -> Result<bool> {
+            crate::dconf::get_bool(
+                "org.gnome.desktop.a11y.applications",
+                "screen-reader-enabled",
+            )
         }
-        pub fn reset_show_battery_percentage() -> Result<(), String> {
-            crate::dconf::reset("org.gnome.desktop.interface", "show-battery-percentage")
+        pub fn reset_screen_reader_enabled() -> Result<()> {
+            crate::dconf::reset(
+                "org.gnome.desktop.a11y.applications",
+                "screen-reader-enabled",
+            )
         }
-        pub fn set_locate_pointer(enabled: bool) -> Result<(), String> {
+        pub fn set_screen_keyboard_enabled(enabled: bool) -> Result<()> {
             crate::dconf::set(
-                "org.gnome.desktop.interface",
-                "locate-pointer",
+                "org.gnome.desktop.a11y.applications",
+                "screen-keyboard-enabled",
                 enabled.to_string().as_str(),
             )
         }
-        pub fn get_locate_pointer() -> Result<bool, String> {
-            let value = crate::dconf::get("org.gnome.desktop.interface", "locate-pointer")?;
-            Ok(value.parse::<bool>().unwrap())
+        pub fn get_screen_keyboard_enabled() -> Result<bool> {
+            crate::dconf::get_bool(
+                "org.gnome.desktop.a11y.applications",
+                "screen-keyboard-enabled",
+            )
         }
-        pub fn reset_locate_pointer() -> Result<(), String> {
-            crate::dconf::reset("org.gnome.desktop.interface", "locate-pointer")
+        pub fn reset_screen_keyboard_enabled() -> Result<()> {
+            crate::dconf::reset(
+                "org.gnome.desktop.a11y.applications",
+                "screen-keyboard-enabled",
+            )
         }
-        pub fn set_cursor_size(size: u32) -> Result<(), String> {
+        pub fn set_magnifier_enabled(enabled: bool) -> Result<()> {
             crate::dconf::set(
-                "org.gnome.desktop.interface",
-                "cursor-size",
-                size.to_string().as_str(),
+                "org.gnome.desktop.a11y.applications",
+                "screen-magnifier-enabled",
+                enabled.to_string().as_str(),
             )
         }
-        pub fn get_cursor_size() -> Result<u32, String> {
-            let value = crate::dconf::get("org.gnome.desktop.interface", "cursor-size")?;
-            Ok(value.parse::<u32>().unwrap())
+        pub fn get_magnifier_enabled() -> Result<bool> {
+            crate::dconf::get_bool(
+                "org.gnome.desktop.a11y.applications",
+                "screen-magnifier-enabled",
+            )
         }
-        pub fn reset_cursor_size() -> Result<(), String> {
-            crate::dconf::reset("org.gnome.desktop.interface", "cursor-size")
+        pub fn reset_magnifier_enabled() -> Result<()> {
+            crate::dconf::reset(
+                "org.gnome.desktop.a11y.applications",
+                "screen-magnifier-enabled",
+            )
+        }
+        /// Zoom level of the magnifier, must be at least `1.0` (no zoom)
+        pub fn set_magnifier_factor(factor: f64) -> Result<()> {
+            if factor.is_nan() || factor < 1.0 {
+                return Err(Error::InvalidArgument(format!(
+                    "Magnifier factor must be >= 1.0, got {}",
+                    factor
+                )));
+            }
+            crate::dconf::set(
+                "org.gnome.desktop.a11y.magnifier",
+                "mag-factor",
+                factor.to_string().as_str(),
+            )
+        }
+        pub fn get_magnifier_factor() -> Result<f64> {
+            let value = crate::dconf::get("org.gnome.desktop.a11y.magnifier", "mag-factor")?;
+            value
+                .parse::<f64>()
+                .map_err(|_| Error::Parse("Unable to read mag-factor".to_string()))
+        }
+        pub fn reset_magnifier_factor() -> Result<()> {
+            crate::dconf::reset("org.gnome.desktop.a11y.magnifier", "mag-factor")
         }
     }
 
     pub mod peripherals {
-        pub fn set_keyboard_press_delay(delay: u32) -> Result<(), String> {
+        use crate::error::{Error, Result};
+
+        /// ## Restore the default keyboard, mouse and touchpad settings
+        /// Resets every key of `org.gnome.desktop.peripherals` and its child schemas, including
+        /// the ones this module has no function for.
+        pub fn reset_all() -> Result<()> {
+            crate::dconf::reset_all("org.gnome.desktop.peripherals")
+        }
+        pub fn set_keyboard_press_delay(delay: u32) -> Result<()> {
             crate::dconf::set(
                 "org.gnome.desktop.peripherals.keyboard",
                 "delay",
                 String::from(delay.to_string()).as_str(),
             )
         }
-        pub fn get_keyboard_press_delay() -> Result<u32, String> {
-            let value: String =
-                crate::dconf::get("org.gnome.desktop.peripherals.keyboard", "delay")?;
-            Ok(value.parse::<u32>().unwrap())
+        pub fn get_keyboard_press_delay() -> Result<u32> {
+            let value = crate::dconf::get("org.gnome.desktop.peripherals.keyboard", "delay")?;
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::Parse("Unable to read delay".to_string()))
         }
-        pub fn reset_keyboard_press_delay() -> Result<(), String> {
+        pub fn reset_keyboard_press_delay() -> Result<()> {
             crate::dconf::reset("org.gnome.desktop.peripherals.keyboard", "delay")
         }
-        pub fn set_keyboard_repeat_interval(interval: u32) -> Result<(), String> {
+        pub fn set_keyboard_repeat_interval(interval: u32) -> Result<()> {
             crate::dconf::set(
                 "org.gnome.desktop.peripherals.keyboard",
                 "repeat-interval",
                 String::from(interval.to_string()).as_str(),
             )
         }
-        pub fn get_keyboard_repeat_interval() -> Result<u32, String> {
+        pub fn get_keyboard_repeat_interval() -> Result<u32> {
             let value =
                 crate::dconf::get("org.gnome.desktop.peripherals.keyboard", "repeat-interval")?;
-            Ok(value.parse::<u32>().unwrap())
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::Parse("Unable to read repeat-interval".to_string()))
         }
-        pub fn reset_keyboard_repeat_interval() -> Result<(), String> {
+        pub fn reset_keyboard_repeat_interval() -> Result<()> {
             crate::dconf::reset("org.gnome.desktop.peripherals.keyboard", "repeat-interval")
         }
-        pub fn set_mouse_natural_scroll(enabled: bool) -> Result<(), String> {
+        /// Restore the Num Lock state of the previous session at login
+        pub fn set_keyboard_remember_numlock_state(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                "org.gnome.desktop.peripherals.keyboard",
+                "remember-numlock-state",
+                enabled.to_string().as_str(),
+            )
+        }
+        pub fn get_keyboard_remember_numlock_state() -> Result<bool> {
+            crate::dconf::get_bool(
+                "org.gnome.desktop.peripherals.keyboard",
+                "remember-numlock-state",
+            )
+        }
+        pub fn reset_keyboard_remember_numlock_state() -> Result<()> {
+            crate::dconf::reset(
+                "org.gnome.desktop.peripherals.keyboard",
+                "remember-numlock-state",
+            )
+        }
+        pub fn set_mouse_natural_scroll(enabled: bool) -> Result<()> {
             crate::dconf::set(
                 "org.gnome.desktop.peripherals.mouse",
                 "natural-scroll",
                 String::from(enabled.to_string()).as_str(),
             )
         }
-        pub fn get_mouse_natural_scroll() -> Result<bool, String> {
-            let value = crate::dconf::get("org.gnome.desktop.peripherals.mouse", "natural-scroll")?;
-            Ok(value.parse::<bool>().unwrap())
+        pub fn get_mouse_natural_scroll() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.peripherals.mouse", "natural-scroll")
         }
-        pub fn reset_mouse_natural_scroll() -> Result<(), String> {
+        pub fn reset_mouse_natural_scroll() -> Result<()> {
             crate::dconf::reset("org.gnome.desktop.peripherals.mouse", "natural-scroll")
         }
-        pub fn set_touchpad_tap_to_click(enabled: bool) -> Result<(), String> {
+        /// Flips the setting, returns the new value
+        pub fn toggle_mouse_natural_scroll() -> Result<bool> {
+            crate::dconf::toggle("org.gnome.desktop.peripherals.mouse", "natural-scroll")
+        }
+        pub fn set_touchpad_tap_to_click(enabled: bool) -> Result<()> {
             crate::dconf::set(
                 "org.gnome.desktop.peripherals.touchpad",
                 "tap-to-click",
                 String::from(enabled.to_string()).as_str(),
             )
         }
-        pub fn get_touchpad_tap_to_click() -> Result<bool, String> {
-            let value =
-                crate::dconf::get("org.gnome.desktop.peripherals.touchpad", "tap-to-click")?;
-            Ok(value.parse::<bool>().unwrap())
+        pub fn get_touchpad_tap_to_click() -> Result<bool> {
+            crate::dconf::get_bool("org.gnome.desktop.peripherals.touchpad", "tap-to-click")
         }
-        pub fn reset_touchpad_tap_to_click() -> Result<(), String> {
+        pub fn reset_touchpad_tap_to_click() -> Result<()> {
             crate::dconf::reset("org.gnome.desktop.peripherals.touchpad", "tap-to-click")
         }
-        pub fn set_two_finger_scroll(enabled: bool) -> Result<(), String> {
+        /// Flips the setting, returns the new value
+        pub fn toggle_touchpad_tap_to_click() -> Result<bool> {
+            crate::dconf::toggle("org.gnome.desktop.peripherals.touchpad", "tap-to-click")
+        }
+        pub fn set_two_finger_scroll(enabled: bool) -> Result<()> {
             crate::dconf::set(
                 "org.gnome.desktop.peripherals.touchpad",
                 "two-finger-scrolling-enabled",
                 String::from(enabled.to_string()).as_str(),
             )
         }
-        pub fn get_two_finger_scroll() -> Result<bool, String> {
-            let value = crate::dconf::get(
+        pub fn get_two_finger_scroll() -> Result<bool> {
+            crate::dconf::get_bool(
                 "org.gnome.desktop.peripherals.touchpad",
                 "two-finger-scrolling-enabled",
-            )?;
-            Ok(value.parse::<bool>().unwrap())
+            )
         }
-        pub fn reset_two_finger_scroll() -> Result<(), String> {
+        pub fn reset_two_finger_scroll() -> Result<()> {
             crate::dconf::reset(
                 "org.gnome.desktop.peripherals.touchpad",
                 "two-finger-scrolling-enabled",
             )
         }
+
+        /// # Graphics tablets
+        /// Settings of Wacom and other drawing tablets, stored per tablet in the relocatable
+        /// `org.gnome.desktop.peripherals.tablet` schema at
+        /// `/org/gnome/desktop/peripherals/tablets/<vendor id>:<product id>/`.
+        /// Tablets are found through udev, so only connected ones are listed.
+        /// Pad button actions live in another schema and aren't covered.
+        pub mod tablet {
+            use std::path::Path;
+
+            use crate::error::Result;
+            use crate::handlers::easy_gnome::display::Monitor;
+
+            const TABLET_SCHEMA: &str = "org.gnome.desktop.peripherals.tablet";
+
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct Tablet {
+                /// `<vendor id>:<product id>` in hex, e.g. `056a:0357`
+                pub id: String,
+                pub name: String,
+            }
+
+            /// How the stylus moves the pointer
+            #[derive(PartialEq, Debug, Clone, Copy)]
+            pub enum MappingMode {
+                /// The tablet area matches the screen, like a pen on paper
+                Absolute,
+                /// The stylus moves the pointer like a mouse
+                Relative,
+            }
+            impl MappingMode {
+                fn as_str(&self) -> &'static str {
+                    match self {
+                        MappingMode::Absolute => "absolute",
+                        MappingMode::Relative => "relative",
+                    }
+                }
+                fn from(mode: &str) -> MappingMode {
+                    match mode {
+                        "relative" => MappingMode::Relative,
+                        _ => MappingMode::Absolute,
+                    }
+                }
+            }
+
+            fn relocatable_schema(tablet_id: &str) -> String {
+                format!(
+                    "{}:/org/gnome/desktop/peripherals/tablets/{}/",
+                    TABLET_SCHEMA, tablet_id
+                )
+            }
+            fn read_trimmed(path: &Path) -> Option<String> {
+                std::fs::read_to_string(path)
+                    .ok()
+                    .map(|value| value.trim().to_string())
+            }
+            /// Whether udev tagged the event device (`major:minor`) as a tablet, pads excluded
+            fn is_tablet(dev: &str) -> bool {
+                let properties = match std::fs::read_to_string(format!("/run/udev/data/c{}", dev)) {
+                    Ok(properties) => properties,
+                    Err(_) => return false,
+                };
+                properties.lines().any(|line| line == "E:ID_INPUT_TABLET=1")
+                    && !properties
+                        .lines()
+                        .any(|line| line == "E:ID_INPUT_TABLET_PAD=1")
+            }
+
+            /// ## Connected tablets
+            /// A tablet exposing several devices (stylus, eraser, touch) is listed once.
+            pub fn list_tablets() -> Vec<Tablet> {
+                let entries = match std::fs::read_dir("/sys/class/input") {
+                    Ok(entries) => entries,
+                    Err(_) => return vec![],
+                };
+                let mut tablets: Vec<Tablet> = Vec::new();
+                for entry in entries.flatten() {
+                    if !entry.file_name().to_string_lossy().starts_with("event") {
+                        continue;
+                    }
+                    let path = entry.path();
+                    match read_trimmed(&path.join("dev")) {
+                        Some(dev) if is_tablet(&dev) => {}
+                        _ => continue,
+                    }
+                    let device = path.join("device");
+                    let (vendor, product) = match (
+                        read_trimmed(&device.join("id/vendor")),
+                        read_trimmed(&device.join("id/product")),
+                    ) {
+                        (Some(vendor), Some(product)) => (vendor, product),
+                        _ => continue,
+                    };
+                    let id = format!("{}:{}", vendor, product);
+                    if tablets.iter().any(|tablet| tablet.id == id) {
+                        continue;
+                    }
+                    tablets.push(Tablet {
+                        id,
+                        name: read_trimmed(&device.join("name")).unwrap_or_default(),
+                    });
+                }
+                tablets
+            }
+
+            pub fn set_mapping_mode(tablet_id: &str, mode: MappingMode) -> Result<()> {
+                crate::dconf::set(
+                    &relocatable_schema(tablet_id),
+                    "mapping",
+                    &crate::dconf::format_string(mode.as_str()),
+                )
+            }
+            pub fn get_mapping_mode(tablet_id: &str) -> Result<MappingMode> {
+                let mode = crate::dconf::get_string(&relocatable_schema(tablet_id), "mapping")?;
+                Ok(MappingMode::from(&mode))
+            }
+            /// ## Map the tablet to one monitor
+            /// `None` maps it to the whole desktop again.
+            pub fn set_mapping(tablet_id: &str, output: Option<&Monitor>) -> Result<()> {
+                let schema = relocatable_schema(tablet_id);
+                let output = match output {
+                    Some(output) => output,
+                    None => return crate::dconf::reset(&schema, "output"),
+                };
+                let current = crate::dconf::get_raw(&schema, "output")?;
+                // GNOME 44 changed the key from an EDID tuple to a list ending with the connector
+                let value = if current.starts_with('(') {
+                    format!(
+                        "({}, {}, {})",
+                        crate::dconf::format_string(&output.vendor),
+                        crate::dconf::format_string(&output.product),
+                        crate::dconf::format_string(&output.serial)
+                    )
+                } else {
+                    crate::dconf::format_string_array(&[
+                        output.vendor.clone(),
+                        output.product.clone(),
+                        output.serial.clone(),
+                        output.connector.clone(),
+                    ])
+                };
+                crate::dconf::set(&schema, "output", &value)
+            }
+            /// Keep the aspect ratio of the monitor when mapping, part of the tablet is unused
+            pub fn set_keep_aspect(tablet_id: &str, enabled: bool) -> Result<()> {
+                crate::dconf::set(
+                    &relocatable_schema(tablet_id),
+                    "keep-aspect",
+                    enabled.to_string().as_str(),
+                )
+            }
+            pub fn get_keep_aspect(tablet_id: &str) -> Result<bool> {
+                crate::dconf::get_bool(&relocatable_schema(tablet_id), "keep-aspect")
+            }
+            /// Rotate the tablet by 180 degrees for left handed use
+            pub fn set_left_handed(tablet_id: &str, enabled: bool) -> Result<()> {
+                crate::dconf::set(
+                    &relocatable_schema(tablet_id),
+                    "left-handed",
+                    enabled.to_string().as_str(),
+                )
+            }
+            pub fn get_left_handed(tablet_id: &str) -> Result<bool> {
+                crate::dconf::get_bool(&relocatable_schema(tablet_id), "left-handed")
+            }
+        }
+
+        /// # Custom keyboard shortcuts
+        /// Custom shortcuts are stored as a list of dconf paths in
+        /// `org.gnome.settings-daemon.plugins.media-keys custom-keybindings`,
+        /// each path holding the `name`, `command` and `binding` of one shortcut
+        /// in the relocatable `org.gnome.settings-daemon.plugins.media-keys.custom-keybinding` schema.
+        pub mod keybindings {
+            use crate::error::{Error, Result};
+
+            const MEDIA_KEYS_SCHEMA: &str = "org.gnome.settings-daemon.plugins.media-keys";
+            const CUSTOM_KEYBINDING_SCHEMA: &str =
+                "org.gnome.settings-daemon.plugins.media-keys.custom-keybinding";
+            const CUSTOM_KEYBINDINGS_PATH: &str =
+                "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings";
+
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct CustomShortcut {
+                /// dconf path of the shortcut, use it to remove the shortcut
+                pub path: String,
+                pub name: String,
+                pub command: String,
+                /// Accelerator like `<Super>t` or `<Primary><Alt>Delete`
+                pub binding: String,
+            }
+
+            fn relocatable_schema(path: &str) -> String {
+                format!("{}:{}", CUSTOM_KEYBINDING_SCHEMA, path)
+            }
+            fn get_custom_keybinding_paths() -> Result<Vec<String>> {
+                let value = crate::dconf::get_raw(MEDIA_KEYS_SCHEMA, "custom-keybindings")?;
+                Ok(crate::dconf::parse_string_array(&value))
+            }
+            fn set_custom_keybinding_paths(paths: &[String]) -> Result<()> {
+                crate::dconf::set(
+                    MEDIA_KEYS_SCHEMA,
+                    "custom-keybindings",
+                    crate::dconf::format_string_array(paths).as_str(),
+                )
+            }
+            fn get_custom_shortcut(path: &str) -> Result<CustomShortcut> {
+                let schema = relocatable_schema(path);
+                let name = crate::dconf::get_raw(&schema, "name")?;
+                let command = crate::dconf::get_raw(&schema, "command")?;
+                let binding = crate::dconf::get_raw(&schema, "binding")?;
+                Ok(CustomShortcut {
+                    path: path.to_string(),
+                    name: crate::dconf::parse_string(&name),
+                    command: crate::dconf::parse_string(&command),
+                    binding: crate::dconf::parse_string(&binding),
+                })
+            }
+
+            pub fn list_custom_shortcuts() -> Result<Vec<CustomShortcut>> {
+                get_custom_keybinding_paths()?
+                    .iter()
+                    .map(|path| get_custom_shortcut(path))
+                    .collect()
+            }
+            /// Shortcuts already using `binding`, compared case-insensitively
+            pub fn find_conflicting_shortcuts(binding: &str) -> Result<Vec<CustomShortcut>> {
+                let shortcuts = list_custom_shortcuts()?;
+                Ok(shortcuts
+                    .into_iter()
+                    .filter(|shortcut| shortcut.binding.eq_ignore_ascii_case(binding))
+                    .collect())
+            }
+            /// Adds a new custom shortcut and returns it along with any existing shortcuts
+            /// that already use the same binding.
+            ///
+            /// GNOME accepts conflicting bindings but only one of them will fire,
+            /// so a non empty list should be shown to the user as a warning.
+            pub fn add_custom_shortcut(
+                name: &str,
+                command: &str,
+                binding: &str,
+            ) -> Result<(CustomShortcut, Vec<CustomShortcut>)> {
+                let conflicts = find_conflicting_shortcuts(binding)?;
+                let mut paths = get_custom_keybinding_paths()?;
+                let path = (0..)
+                    .map(|index| format!("{}/custom{}/", CUSTOM_KEYBINDINGS_PATH, index))
+                    .find(|path| !paths.contains(path))
+                    .unwrap();
+
+                let schema = relocatable_schema(&path);
+                crate::dconf::set(&schema, "name", &crate::dconf::format_string(name))?;
+                crate::dconf::set(&schema, "command", &crate::dconf::format_string(command))?;
+                crate::dconf::set(&schema, "binding", &crate::dconf::format_string(binding))?;
+                paths.push(path.clone());
+                set_custom_keybinding_paths(&paths)?;
+
+                let shortcut = CustomShortcut {
+                    path,
+                    name: name.to_string(),
+                    command: command.to_string(),
+                    binding: binding.to_string(),
+                };
+                Ok((shortcut, conflicts))
+            }
+            /// Removes the shortcut stored at `path` (see `CustomShortcut::path`)
+            pub fn remove_custom_shortcut(path: &str) -> Result<()> {
+                let mut paths = get_custom_keybinding_paths()?;
+                if !paths.iter().any(|p| p == path) {
+                    return Err(Error::InvalidArgument(format!(
+                        "No custom shortcut at {}",
+                        path
+                    )));
+                }
+                paths.retain(|p| p != path);
+                set_custom_keybinding_paths(&paths)?;
+
+                let schema = relocatable_schema(path);
+                crate::dconf::reset(&schema, "name")?;
+                crate::dconf::reset(&schema, "command")?;
+                crate::dconf::reset(&schema, "binding")
+            }
+        }
+    }
+
+    /// # Lock keys
+    /// State of Caps Lock and Num Lock, e.g. for an on-screen indicator.
+    /// - On X11 it comes from XKB through `xset q`, which needs `xset` installed.
+    /// - Elsewhere (Wayland, TTY) the keyboard LEDs in `/sys/class/leds` are read, the
+    ///   compositor keeps them in sync with its lock state. Without a keyboard exposing
+    ///   LEDs `Error::NotSupported` is returned.
+    ///
+    /// Changing the state is only possible on X11, by pressing the key through `xdotool`
+    /// (which must be installed). Wayland compositors don't let clients inject key presses,
+    /// so the setters return `Error::NotSupported` there.
+    pub mod keyboard {
+        use std::path::PathBuf;
+        use std::process::Command;
+        use std::time::Duration;
+
+        use futures_util::Stream;
+
+        use crate::error::{Error, Result};
+        use crate::runtime::sleep;
+
+        use super::session::{self, SessionType};
+
+        /// How often `watch_lock_keys` checks the state, there is no change notification
+        pub const LOCK_KEYS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+        #[derive(PartialEq, Debug, Clone, Copy)]
+        pub struct LockKeys {
+            pub caps_lock: bool,
+            pub num_lock: bool,
+        }
+
+        /// Reads `on`/`off` after `label` in the output of `xset q`, e.g.
+        /// `00: Caps Lock:   off    01: Num Lock:    on     02: Scroll Lock: off`
+        fn parse_xset_led(output: &str, label: &str) -> Option<bool> {
+            let (_, after) = output.split_once(label)?;
+            match after.split_whitespace().next()? {
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => None,
+            }
+        }
+        fn xset_lock_keys() -> Result<LockKeys> {
+            let output = Command::new("xset").arg("q").output()?;
+            if !output.status.success() {
+                return Err(Error::Command(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ));
+            }
+            let output = String::from_utf8_lossy(&output.stdout);
+            let led = |label: &str| {
+                parse_xset_led(&output, label)
+                    .ok_or_else(|| Error::Parse(format!("No {} in xset output", label)))
+            };
+            Ok(LockKeys {
+                caps_lock: led("Caps Lock:")?,
+                num_lock: led("Num Lock:")?,
+            })
+        }
+        /// LEDs are named like `input3::capslock`, one per keyboard
+        fn led_dirs(suffix: &str) -> Vec<PathBuf> {
+            let entries = match std::fs::read_dir("/sys/class/leds") {
+                Ok(entries) => entries,
+                Err(_) => return Vec::new(),
+            };
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().ends_with(suffix))
+                .map(|entry| entry.path())
+                .collect()
+        }
+        /// On when the LED of any keyboard is lit
+        fn sysfs_led(suffix: &str) -> Result<bool> {
+            let dirs = led_dirs(suffix);
+            if dirs.is_empty() {
+                return Err(Error::NotSupported);
+            }
+            Ok(dirs.iter().any(|dir| {
+                std::fs::read_to_string(dir.join("brightness"))
+                    .map(|brightness| brightness.trim() != "0")
+                    .unwrap_or(false)
+            }))
+        }
+
+        pub fn lock_keys() -> Result<LockKeys> {
+            if session::session_type() == SessionType::X11 {
+                return xset_lock_keys();
+            }
+            Ok(LockKeys {
+                caps_lock: sysfs_led("::capslock")?,
+                num_lock: sysfs_led("::numlock")?,
+            })
+        }
+        pub fn caps_lock_on() -> Result<bool> {
+            Ok(lock_keys()?.caps_lock)
+        }
+        pub fn num_lock_on() -> Result<bool> {
+            Ok(lock_keys()?.num_lock)
+        }
+        /// Presses `key` when the current state differs from `on`
+        fn set_lock_key(key: &str, current: bool, on: bool) -> Result<()> {
+            if current == on {
+                return Ok(());
+            }
+            let output = Command::new("xdotool").args(["key", key]).output()?;
+            if !output.status.success() {
+                return Err(Error::Command(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ));
+            }
+            Ok(())
+        }
+        pub fn set_caps_lock(on: bool) -> Result<()> {
+            if session::session_type() != SessionType::X11 {
+                return Err(Error::NotSupported);
+            }
+            set_lock_key("Caps_Lock", xset_lock_keys()?.caps_lock, on)
+        }
+        /// Handy for kiosks that want Num Lock on at startup. On Wayland, GNOME can restore
+        /// the last Num Lock state at login instead, see
+        /// `peripherals::set_keyboard_remember_numlock_state`.
+        pub fn set_num_lock(on: bool) -> Result<()> {
+            if session::session_type() != SessionType::X11 {
+                return Err(Error::NotSupported);
+            }
+            set_lock_key("Num_Lock", xset_lock_keys()?.num_lock, on)
+        }
+        /// ## Emit the lock keys state every time it changes
+        /// Polls every `LOCK_KEYS_POLL_INTERVAL`, the current state is emitted first.
+        /// The stream ends when the state can't be read anymore.
+        pub fn watch_lock_keys() -> impl Stream<Item = LockKeys> {
+            futures_util::stream::unfold(None, |last: Option<LockKeys>| async move {
+                loop {
+                    if last.is_some() {
+                        sleep(LOCK_KEYS_POLL_INTERVAL).await;
+                    }
+                    let state = lock_keys().ok()?;
+                    if last != Some(state) {
+                        return Some((state, Some(state)));
+                    }
+                }
+            })
+        }
+    }
+
+    /// # Quick settings
+    /// The toggles of GNOME's quick settings menu behind one facade, for custom panels.
+    /// Every toggle delegates to its module: `rfkill` for the radios, `interface` for the
+    /// style, `nightlight` and `power`. Do not disturb is GNOME's `show-banners` key.
+    pub mod quick_settings {
+        use crate::error::Result;
+        use crate::handlers::easy_gnome::interface::{self, ColorScheme};
+        use crate::handlers::easy_gnome::{nightlight, power, rfkill, PowerProfile};
+
+        const NOTIFICATIONS_SCHEMA: &str = "org.gnome.desktop.notifications";
+
+        /// Every field is `None` when it couldn't be read, e.g. without NetworkManager or
+        /// power-profiles-daemon
+        #[derive(Debug, Clone, PartialEq, Default)]
+        pub struct QuickSettingsState {
+            pub wifi: Option<bool>,
+            pub bluetooth: Option<bool>,
+            pub airplane_mode: Option<bool>,
+            pub dark_mode: Option<bool>,
+            pub night_light: Option<bool>,
+            pub do_not_disturb: Option<bool>,
+            pub power_profile: Option<PowerProfile>,
+        }
+
+        pub async fn read_all() -> QuickSettingsState {
+            let radios = rfkill::get_radio_state().await.ok();
+            let power_profile = if power::is_power_profiles_available().await {
+                power::get_power_profile().await.ok()
+            } else {
+                None
+            };
+            QuickSettingsState {
+                wifi: radios.map(|radios| !radios.wifi_blocked),
+                bluetooth: radios.map(|radios| !radios.bluetooth_blocked),
+                airplane_mode: radios.map(|radios| radios.airplane_mode),
+                dark_mode: get_dark_mode().ok(),
+                night_light: nightlight::get_nightlight_active().ok(),
+                do_not_disturb: get_do_not_disturb().ok(),
+                power_profile,
+            }
+        }
+
+        pub async fn set_wifi(enabled: bool) -> Result<()> {
+            rfkill::set_wifi_enabled(enabled).await
+        }
+        pub async fn set_bluetooth(enabled: bool) -> Result<()> {
+            rfkill::set_bluetooth_enabled(enabled).await
+        }
+        pub async fn set_airplane_mode(enabled: bool) -> Result<()> {
+            rfkill::set_airplane_mode(enabled).await
+        }
+        /// Dark style, the light style sets the `Default` color scheme like GNOME does
+        pub fn set_dark_mode(enabled: bool) -> Result<()> {
+            let scheme = if enabled {
+                ColorScheme::PreferDark
+            } else {
+                ColorScheme::Default
+            };
+            interface::set_color_scheme(scheme)
+        }
+        pub fn get_dark_mode() -> Result<bool> {
+            Ok(interface::get_color_scheme()? == ColorScheme::PreferDark)
+        }
+        /// Flips the dark style, returns whether it's now dark
+        pub fn toggle_dark_mode() -> Result<bool> {
+            let dark_mode = !get_dark_mode()?;
+            set_dark_mode(dark_mode)?;
+            Ok(dark_mode)
+        }
+        pub fn set_night_light(enabled: bool) -> Result<()> {
+            nightlight::set_nightlight_active(enabled)
+        }
+        /// Hides notification banners, they still go to the message list
+        pub fn set_do_not_disturb(enabled: bool) -> Result<()> {
+            crate::dconf::set(
+                NOTIFICATIONS_SCHEMA,
+                "show-banners",
+                (!enabled).to_string().as_str(),
+            )
+        }
+        pub fn get_do_not_disturb() -> Result<bool> {
+            Ok(!crate::dconf::get_bool(
+                NOTIFICATIONS_SCHEMA,
+                "show-banners",
+            )?)
+        }
+        pub async fn set_power_profile(profile: PowerProfile) -> Result<()> {
+            power::set_power_profile(profile).await
+        }
+    }
+
+    /// # Settings backup
+    /// Snapshot of every setting the typed modules manage, to save it (e.g. as JSON with
+    /// serde) and apply it again later or on another machine.
+    pub mod settings {
+        use gio::prelude::*;
+        use gio::SettingsSchemaSource;
+        use serde::{Deserialize, Serialize};
+
+        use crate::error::{Error, Result};
+        use crate::handlers::easy_gnome::interface::{
+            AccentColor, ButtonLayout, ClockFormat, ColorScheme,
+        };
+        use crate::handlers::easy_gnome::wm::FocusMode;
+        use crate::handlers::easy_gnome::{
+            accessibility, interface, nightlight, peripherals, privacy, wm, workspaces,
+        };
+
+        /// Every field is `None` when it couldn't be read, and is then left untouched by
+        /// `import`. Missing fields deserialize to `None` too.
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct InterfaceSettings {
+            pub show_battery_percentage: Option<bool>,
+            pub locate_pointer: Option<bool>,
+            pub cursor_size: Option<u32>,
+            pub clock_format: Option<ClockFormat>,
+            pub clock_show_seconds: Option<bool>,
+            pub clock_show_weekday: Option<bool>,
+            pub clock_show_date: Option<bool>,
+            pub color_scheme: Option<ColorScheme>,
+            pub accent_color: Option<AccentColor>,
+            pub animations_enabled: Option<bool>,
+            pub icon_theme: Option<String>,
+            pub cursor_theme: Option<String>,
+            pub gtk_theme: Option<String>,
+            pub hot_corners_enabled: Option<bool>,
+            pub edge_tiling: Option<bool>,
+            pub window_buttons_layout: Option<ButtonLayout>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct PeripheralsSettings {
+            pub keyboard_press_delay: Option<u32>,
+            pub keyboard_repeat_interval: Option<u32>,
+            pub mouse_natural_scroll: Option<bool>,
+            pub touchpad_tap_to_click: Option<bool>,
+            pub two_finger_scroll: Option<bool>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct NightLightSettings {
+            pub active: Option<bool>,
+            pub temperature: Option<u32>,
+            pub schedule_automatic: Option<bool>,
+            pub schedule_from: Option<f64>,
+            pub schedule_to: Option<f64>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct AccessibilitySettings {
+            pub high_contrast: Option<bool>,
+            pub text_scaling_factor: Option<f64>,
+            pub screen_reader_enabled: Option<bool>,
+            pub screen_keyboard_enabled: Option<bool>,
+            pub magnifier_enabled: Option<bool>,
+            pub magnifier_factor: Option<f64>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct PrivacySettings {
+            pub remember_recent_files: Option<bool>,
+            pub remove_old_trash_files: Option<bool>,
+            pub old_files_age: Option<u32>,
+            pub report_technical_problems: Option<bool>,
+            pub camera_enabled: Option<bool>,
+            pub microphone_enabled: Option<bool>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct WorkspacesSettings {
+            pub count: Option<u32>,
+            pub dynamic: Option<bool>,
+            pub only_on_primary: Option<bool>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct WmSettings {
+            pub focus_mode: Option<FocusMode>,
+            pub auto_raise: Option<bool>,
+            pub raise_on_click: Option<bool>,
+            pub attach_modal_dialogs: Option<bool>,
+            pub center_new_windows: Option<bool>,
+            pub resize_with_right_button: Option<bool>,
+            pub mouse_button_modifier: Option<String>,
+        }
+        #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct SettingsSnapshot {
+            pub interface: InterfaceSettings,
+            pub peripherals: PeripheralsSettings,
+            pub nightlight: NightLightSettings,
+            pub accessibility: AccessibilitySettings,
+            pub privacy: PrivacySettings,
+            pub workspaces: WorkspacesSettings,
+            pub wm: WmSettings,
+        }
+
+        /// Every (schema, key) the typed modules read or write
+        const MANAGED_KEYS: [(&str, &str); 50] = [
+            (
+                "org.gnome.desktop.a11y.applications",
+                "screen-keyboard-enabled",
+            ),
+            (
+                "org.gnome.desktop.a11y.applications",
+                "screen-magnifier-enabled",
+            ),
+            (
+                "org.gnome.desktop.a11y.applications",
+                "screen-reader-enabled",
+            ),
+            ("org.gnome.desktop.a11y.interface", "high-contrast"),
+            ("org.gnome.desktop.a11y.magnifier", "mag-factor"),
+            ("org.gnome.desktop.interface", "accent-color"),
+            ("org.gnome.desktop.interface", "clock-format"),
+            ("org.gnome.desktop.interface", "clock-show-date"),
+            ("org.gnome.desktop.interface", "clock-show-seconds"),
+            ("org.gnome.desktop.interface", "clock-show-weekday"),
+            ("org.gnome.desktop.interface", "color-scheme"),
+            ("org.gnome.desktop.interface", "cursor-size"),
+            ("org.gnome.desktop.interface", "cursor-theme"),
+            ("org.gnome.desktop.interface", "enable-animations"),
+            ("org.gnome.desktop.interface", "enable-hot-corners"),
+            ("org.gnome.desktop.interface", "gtk-theme"),
+            ("org.gnome.desktop.interface", "icon-theme"),
+            ("org.gnome.desktop.interface", "locate-pointer"),
+            ("org.gnome.desktop.interface", "show-battery-percentage"),
+            ("org.gnome.desktop.interface", "text-scaling-factor"),
+            ("org.gnome.desktop.peripherals.keyboard", "delay"),
+            ("org.gnome.desktop.peripherals.keyboard", "repeat-interval"),
+            ("org.gnome.desktop.peripherals.mouse", "natural-scroll"),
+            ("org.gnome.desktop.peripherals.touchpad", "tap-to-click"),
+            (
+                "org.gnome.desktop.peripherals.touchpad",
+                "two-finger-scrolling-enabled",
+            ),
+            ("org.gnome.desktop.privacy", "disable-camera"),
+            ("org.gnome.desktop.privacy", "disable-microphone"),
+            ("org.gnome.desktop.privacy", "old-files-age"),
+            ("org.gnome.desktop.privacy", "remember-recent-files"),
+            ("org.gnome.desktop.privacy", "remove-old-trash-files"),
+            ("org.gnome.desktop.privacy", "report-technical-problems"),
+            ("org.gnome.desktop.wm.preferences", "auto-raise"),
+            ("org.gnome.desktop.wm.preferences", "button-layout"),
+            ("org.gnome.desktop.wm.preferences", "focus-mode"),
+            ("org.gnome.desktop.wm.preferences", "mouse-button-modifier"),
+            ("org.gnome.desktop.wm.preferences", "num-workspaces"),
+            ("org.gnome.desktop.wm.preferences", "raise-on-click"),
+            (
+                "org.gnome.desktop.wm.preferences",
+                "resize-with-right-button",
+            ),
+            ("org.gnome.mutter", "attach-modal-dialogs"),
+            ("org.gnome.mutter", "center-new-windows"),
+            ("org.gnome.mutter", "dynamic-workspaces"),
+            ("org.gnome.mutter", "edge-tiling"),
+            ("org.gnome.mutter", "workspaces-only-on-primary"),
+            (
+                "org.gnome.settings-daemon.plugins.color",
+                "night-light-enabled",
+            ),
+            (
+                "org.gnome.settings-daemon.plugins.color",
+                "night-light-schedule-automatic",
+            ),
+            (
+                "org.gnome.settings-daemon.plugins.color",
+                "night-light-schedule-from",
+            ),
+            (
+                "org.gnome.settings-daemon.plugins.color",
+                "night-light-schedule-to",
+            ),
+            (
+                "org.gnome.settings-daemon.plugins.color",
+                "night-light-temperature",
+            ),
+            (
+                "org.gnome.settings-daemon.plugins.media-keys",
+                "custom-keybindings",
+            ),
+            ("org.gnome.shell", "disable-user-extensions"),
+        ];
+
+        /// ## Keys changed from their default
+        /// (schema, key, current value) of every key the crate manages that doesn't have its
+        /// default value, in GVariant text format like `gsettings get` prints it.
+        /// Keys missing from the installed schemas are skipped.
+        pub fn non_default_keys() -> Vec<(String, String, String)> {
+            let source = match SettingsSchemaSource::default() {
+                Some(source) => source,
+                None => return vec![],
+            };
+            MANAGED_KEYS
+                .iter()
+                .filter_map(|(schema_id, key)| {
+                    let schema = source.lookup(schema_id, true)?;
+                    if !schema.has_key(key) {
+                        return None;
+                    }
+                    // Creating settings for a schema that isn't installed aborts, hence the lookup
+                    let settings = gio::Settings::new(schema_id);
+                    let value = settings.value(key);
+                    if settings.default_value(key).as_ref() == Some(&value) {
+                        return None;
+                    }
+                    Some((
+                        schema_id.to_string(),
+                        key.to_string(),
+                        value.print(true).to_string(),
+                    ))
+                })
+                .collect()
+        }
+        /// ## Read the current settings
+        pub fn export() -> SettingsSnapshot {
+            SettingsSnapshot {
+                interface: InterfaceSettings {
+                    show_battery_percentage: interface::get_show_battery_percentage().ok(),
+                    locate_pointer: interface::get_locate_pointer().ok(),
+                    cursor_size: interface::get_cursor_size().ok(),
+                    clock_format: interface::get_clock_format().ok(),
+                    clock_show_seconds: interface::get_clock_show_seconds().ok(),
+                    clock_show_weekday: interface::get_clock_show_weekday().ok(),
+                    clock_show_date: interface::get_clock_show_date().ok(),
+                    color_scheme: interface::get_color_scheme().ok(),
+                    accent_color: interface::get_accent_color().ok(),
+                    animations_enabled: interface::get_animations_enabled().ok(),
+                    icon_theme: interface::get_icon_theme().ok(),
+                    cursor_theme: interface::get_cursor_theme().ok(),
+                    gtk_theme: interface::get_gtk_theme().ok(),
+                    hot_corners_enabled: interface::get_hot_corners_enabled().ok(),
+                    edge_tiling: interface::get_edge_tiling().ok(),
+                    window_buttons_layout: interface::get_window_buttons_layout().ok(),
+                },
+                peripherals: PeripheralsSettings {
+                    keyboard_press_delay: peripherals::get_keyboard_press_delay().ok(),
+                    keyboard_repeat_interval: peripherals::get_keyboard_repeat_interval().ok(),
+                    mouse_natural_scroll: peripherals::get_mouse_natural_scroll().ok(),
+                    touchpad_tap_to_click: peripherals::get_touchpad_tap_to_click().ok(),
+                    two_finger_scroll: peripherals::get_two_finger_scroll().ok(),
+                },
+                nightlight: NightLightSettings {
+                    active: nightlight::get_nightlight_active().ok(),
+                    temperature: nightlight::get_temperature().ok(),
+                    schedule_automatic: nightlight::get_schedule_automatic().ok(),
+                    schedule_from: nightlight::get_schedule_from().ok(),
+                    schedule_to: nightlight::get_schedule_to().ok(),
+                },
+                accessibility: AccessibilitySettings {
+                    high_contrast: accessibility::get_high_contrast().ok(),
+                    text_scaling_factor: accessibility::get_text_scaling_factor().ok(),
+                    screen_reader_enabled: accessibility::get_screen_reader_enabled().ok(),
+                    screen_keyboard_enabled: accessibility::get_screen_keyboard_enabled().ok(),
+                    magnifier_enabled: accessibility::get_magnifier_enabled().ok(),
+                    magnifier_factor: accessibility::get_magnifier_factor().ok(),
+                },
+                privacy: PrivacySettings {
+                    remember_recent_files: privacy::get_remember_recent_files().ok(),
+                    remove_old_trash_files: privacy::get_remove_old_trash_files().ok(),
+                    old_files_age: privacy::get_old_files_age().ok(),
+                    report_technical_problems: privacy::get_report_technical_problems().ok(),
+                    camera_enabled: privacy::get_camera_enabled().ok(),
+                    microphone_enabled: privacy::get_microphone_enabled().ok(),
+                },
+                workspaces: WorkspacesSettings {
+                    count: workspaces::get_count().ok(),
+                    dynamic: workspaces::get_dynamic().ok(),
+                    only_on_primary: workspaces::get_workspaces_only_on_primary().ok(),
+                },
+                wm: WmSettings {
+                    focus_mode: wm::get_focus_mode().ok(),
+                    auto_raise: wm::get_auto_raise().ok(),
+                    raise_on_click: wm::get_raise_on_click().ok(),
+                    attach_modal_dialogs: wm::get_attach_modal_dialogs().ok(),
+                    center_new_windows: wm::get_center_new_windows().ok(),
+                    resize_with_right_button: wm::get_resize_with_right_button().ok(),
+                    mouse_button_modifier: wm::get_mouse_button_modifier().ok(),
+                },
+            }
+        }
+
+        /// Applies `value` when it's set, keeping the error to report it
+        fn apply<T>(
+            errors: &mut Vec<Error>,
+            value: &Option<T>,
+            set: impl FnOnce(&T) -> Result<()>,
+        ) {
+            if let Some(value) = value {
+                if let Err(error) = set(value) {
+                    errors.push(error);
+                }
+            }
+        }
+
+        /// ## Apply a snapshot
+        /// Every setting is applied even when some fail, the errors of those that failed
+        /// are returned.
+        pub fn import(snapshot: &SettingsSnapshot) -> std::result::Result<(), Vec<Error>> {
+            let mut errors = vec![];
+
+            let i = &snapshot.interface;
+            apply(&mut errors, &i.show_battery_percentage, |v| {
+                interface::set_show_battery_percentage(*v)
+            });
+            apply(&mut errors, &i.locate_pointer, |v| {
+                interface::set_locate_pointer(*v)
+            });
+            apply(&mut errors, &i.cursor_size, |v| {
+                interface::set_cursor_size(*v)
+            });
+            apply(&mut errors, &i.clock_format, |v| {
+                interface::set_clock_format(*v)
+            });
+            apply(&mut errors, &i.clock_show_seconds, |v| {
+                interface::set_clock_show_seconds(*v)
+            });
+            apply(&mut errors, &i.clock_show_weekday, |v| {
+                interface::set_clock_show_weekday(*v)
+            });
+            apply(&mut errors, &i.clock_show_date, |v| {
+                interface::set_clock_show_date(*v)
+            });
+            apply(&mut errors, &i.color_scheme, |v| {
+                interface::set_color_scheme(*v)
+            });
+            apply(&mut errors, &i.accent_color, |v| {
+                interface::set_accent_color(*v)
+            });
+            apply(&mut errors, &i.animations_enabled, |v| {
+                interface::set_animations_enabled(*v)
+            });
+            apply(&mut errors, &i.icon_theme, |v| interface::set_icon_theme(v));
+            apply(&mut errors, &i.cursor_theme, |v| {
+                interface::set_cursor_theme(v)
+            });
+            apply(&mut errors, &i.gtk_theme, |v| interface::set_gtk_theme(v));
+            apply(&mut errors, &i.hot_corners_enabled, |v| {
+                interface::set_hot_corners_enabled(*v)
+            });
+            apply(&mut errors, &i.edge_tiling, |v| {
+                interface::set_edge_tiling(*v)
+            });
+            apply(&mut errors, &i.window_buttons_layout, |v| {
+                interface::set_window_buttons_layout(v)
+            });
+
+            let p = &snapshot.peripherals;
+            apply(&mut errors, &p.keyboard_press_delay, |v| {
+                peripherals::set_keyboard_press_delay(*v)
+            });
+            apply(&mut errors, &p.keyboard_repeat_interval, |v| {
+                peripherals::set_keyboard_repeat_interval(*v)
+            });
+            apply(&mut errors, &p.mouse_natural_scroll, |v| {
+                peripherals::set_mouse_natural_scroll(*v)
+            });
+            apply(&mut errors, &p.touchpad_tap_to_click, |v| {
+                peripherals::set_touchpad_tap_to_click(*v)
+            });
+            apply(&mut errors, &p.two_finger_scroll, |v| {
+                peripherals::set_two_finger_scroll(*v)
+            });
+
+            let n = &snapshot.nightlight;
+            apply(&mut errors, &n.active, |v| {
+                nightlight::set_nightlight_active(*v)
+            });
+            apply(&mut errors, &n.temperature, |v| {
+                nightlight::set_temperature(*v)
+            });
+            apply(&mut errors, &n.schedule_automatic, |v| {
+                nightlight::set_schedule_automatic(*v)
+            });
+            apply(&mut errors, &n.schedule_from, |v| {
+                nightlight::set_schedule_from(*v)
+            });
+            apply(&mut errors, &n.schedule_to, |v| {
+                nightlight::set_schedule_to(*v)
+            });
+
+            let a = &snapshot.accessibility;
+            apply(&mut errors, &a.high_contrast, |v| {
+                accessibility::set_high_contrast(*v)
+            });
+            apply(&mut errors, &a.text_scaling_factor, |v| {
+                accessibility::set_text_scaling_factor(*v)
+            });
+            apply(&mut errors, &a.screen_reader_enabled, |v| {
+                accessibility::set_screen_reader_enabled(*v)
+            });
+            apply(&mut errors, &a.screen_keyboard_enabled, |v| {
+                accessibility::set_screen_keyboard_enabled(*v)
+            });
+            apply(&mut errors, &a.magnifier_enabled, |v| {
+                accessibility::set_magnifier_enabled(*v)
+            });
+            apply(&mut errors, &a.magnifier_factor, |v| {
+                accessibility::set_magnifier_factor(*v)
+            });
+
+            let p = &snapshot.privacy;
+            apply(&mut errors, &p.remember_recent_files, |v| {
+                privacy::set_remember_recent_files(*v)
+            });
+            apply(&mut errors, &p.remove_old_trash_files, |v| {
+                privacy::set_remove_old_trash_files(*v)
+            });
+            apply(&mut errors, &p.old_files_age, |v| {
+                privacy::set_old_files_age(*v)
+            });
+            apply(&mut errors, &p.report_technical_problems, |v| {
+                privacy::set_report_technical_problems(*v)
+            });
+            apply(&mut errors, &p.camera_enabled, |v| {
+                privacy::set_camera_enabled(*v)
+            });
+            apply(&mut errors, &p.microphone_enabled, |v| {
+                privacy::set_microphone_enabled(*v)
+            });
+
+            // Dynamic workspaces first, the count is ignored while they're on
+            let w = &snapshot.workspaces;
+            apply(&mut errors, &w.dynamic, |v| workspaces::set_dynamic(*v));
+            apply(&mut errors, &w.count, |v| workspaces::set_count(*v));
+            apply(&mut errors, &w.only_on_primary, |v| {
+                workspaces::set_workspaces_only_on_primary(*v)
+            });
+
+            let w = &snapshot.wm;
+            apply(&mut errors, &w.focus_mode, |v| wm::set_focus_mode(*v));
+            apply(&mut errors, &w.auto_raise, |v| wm::set_auto_raise(*v));
+            apply(&mut errors, &w.raise_on_click, |v| {
+                wm::set_raise_on_click(*v)
+            });
+            apply(&mut errors, &w.attach_modal_dialogs, |v| {
+                wm::set_attach_modal_dialogs(*v)
+            });
+            apply(&mut errors, &w.center_new_windows, |v| {
+                wm::set_center_new_windows(*v)
+            });
+            apply(&mut errors, &w.resize_with_right_button, |v| {
+                wm::set_resize_with_right_button(*v)
+            });
+            apply(&mut errors, &w.mouse_button_modifier, |v| {
+                wm::set_mouse_button_modifier(v)
+            });
+
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        }
     }
 }
@@ -1,5 +1,12 @@
 #![feature(ascii_char)]
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod dconf;
+pub mod error;
 pub mod handlers;
+pub mod retry;
+mod runtime;
 #[cfg(test)]
 pub mod tests;
+pub mod timeout;
+mod watch;
@@ -0,0 +1,95 @@
+//! # Retries
+//! GNOME Shell leaves the bus for a moment while it restarts (`Alt+F2 r` on X11), calls made
+//! meanwhile fail with `ServiceUnknown` or `NoReply`. The shell-facing functions (extensions,
+//! screenshot) retry those errors with exponential backoff, other errors are returned right away.
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::runtime::sleep;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Calls made in total, 1 disables retries
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled after every attempt
+    pub initial_delay: Duration,
+    /// Longest wait between two attempts
+    pub max_delay: Duration,
+}
+impl RetryPolicy {
+    /// 5 attempts over about 1.5 seconds, long enough for a shell restart
+    pub const DEFAULT: RetryPolicy = RetryPolicy {
+        max_attempts: 5,
+        initial_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+    };
+    /// Fail on the first error
+    pub const NEVER: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::DEFAULT
+    }
+}
+
+static RETRY_POLICY: RwLock<RetryPolicy> = RwLock::new(RetryPolicy::DEFAULT);
+
+/// Sets the policy used by every retried call from now on
+pub fn set_retry_policy(policy: RetryPolicy) {
+    *RETRY_POLICY.write().unwrap() = policy;
+}
+pub fn get_retry_policy() -> RetryPolicy {
+    *RETRY_POLICY.read().unwrap()
+}
+
+const TRANSIENT_ERROR_NAMES: [&str; 2] = [
+    "org.freedesktop.DBus.Error.ServiceUnknown",
+    "org.freedesktop.DBus.Error.NoReply",
+];
+
+fn is_transient(error: &zbus::Error) -> bool {
+    match error {
+        zbus::Error::MethodError(name, _, _) => TRANSIENT_ERROR_NAMES.contains(&name.as_str()),
+        zbus::Error::FDO(error) => matches!(
+            **error,
+            zbus::fdo::Error::ServiceUnknown(_) | zbus::fdo::Error::NoReply(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Runs `call` until it succeeds, fails with a non transient error or the attempts of the
+/// current `RetryPolicy` run out. Each attempt is bounded by the default timeout.
+pub(crate) async fn with_retry<T, F, Fut>(mut call: F) -> zbus::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = zbus::Result<T>>,
+{
+    with_retry_unbounded(|| crate::timeout::with_default_timeout(call())).await
+}
+
+/// `with_retry` without the default timeout, for calls waiting on the user
+pub(crate) async fn with_retry_unbounded<T, F, Fut>(mut call: F) -> zbus::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = zbus::Result<T>>,
+{
+    let policy = get_retry_policy();
+    let mut delay = policy.initial_delay;
+    let mut attempt = 1;
+    loop {
+        match call().await {
+            Err(error) if attempt < policy.max_attempts && is_transient(&error) => {
+                sleep(delay).await;
+                delay = (delay * 2).min(policy.max_delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! # Runtime
+//! Timers and `block_on` on the async runtime zbus runs on, picked by the `async-io`
+//! (default) and `tokio` features. With `tokio`, the caller's runtime must have the time
+//! driver enabled, which `#[tokio::main]` does.
+use std::future::Future;
+#[cfg(feature = "tokio")]
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Runs `future` to completion on the current thread, for the blocking API and the threads
+/// of the guards
+#[cfg(not(feature = "tokio"))]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    async_io::block_on(future)
+}
+/// Runs `future` to completion on the current thread, for the blocking API and the threads
+/// of the guards. Panics inside a tokio runtime, like tokio's own `block_on`.
+#[cfg(feature = "tokio")]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_io()
+                .enable_time()
+                .build()
+                .expect("Unable to start the tokio runtime")
+        })
+        .block_on(future)
+}
+
+/// Runs blocking `call` (e.g. file IO) on its own thread, so async functions don't stall
+/// the executor. A panic of `call` is resumed in the caller.
+pub(crate) async fn unblock<T: Send + 'static>(call: impl FnOnce() -> T + Send + 'static) -> T {
+    let (sender, receiver) = futures_channel::oneshot::channel();
+    let thread = std::thread::spawn(move || {
+        let _ = sender.send(call());
+    });
+    match receiver.await {
+        Ok(output) => output,
+        // The sender is only dropped unsent when `call` panicked
+        Err(_) => match thread.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("The thread finished without sending"),
+        },
+    }
+}
+
+/// Waits for `duration` without blocking the thread
+#[cfg(not(feature = "tokio"))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_io::Timer::after(duration).await;
+}
+/// Waits for `duration` without blocking the thread
+#[cfg(feature = "tokio")]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
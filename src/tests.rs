@@ -1,61 +1,234 @@
 use crate::handlers::easy_gnome;
+use crate::handlers::easy_gnome::accessibility;
 use crate::handlers::easy_gnome::battery;
+use crate::handlers::easy_gnome::clipboard;
+use crate::handlers::easy_gnome::color_management;
+use crate::handlers::easy_gnome::display;
 use crate::handlers::easy_gnome::extensions;
+use crate::handlers::easy_gnome::gamma;
+use crate::handlers::easy_gnome::image_utils::{self, ImageFormat};
 use crate::handlers::easy_gnome::interface;
+use crate::handlers::easy_gnome::keyboard;
+use crate::handlers::easy_gnome::location;
+use crate::handlers::easy_gnome::media_keys;
 use crate::handlers::easy_gnome::nightlight;
 use crate::handlers::easy_gnome::peripherals;
+use crate::handlers::easy_gnome::peripherals::keybindings;
+use crate::handlers::easy_gnome::peripherals::tablet;
 use crate::handlers::easy_gnome::power;
+use crate::handlers::easy_gnome::privacy;
+use crate::handlers::easy_gnome::quick_settings;
+use crate::handlers::easy_gnome::rfkill;
 use crate::handlers::easy_gnome::screen;
+use crate::handlers::easy_gnome::screencast;
 use crate::handlers::easy_gnome::screenshot;
+use crate::handlers::easy_gnome::search_provider;
+use crate::handlers::easy_gnome::session;
+use crate::handlers::easy_gnome::settings;
+use crate::handlers::easy_gnome::shell;
+use crate::handlers::easy_gnome::sound;
+use crate::handlers::easy_gnome::status;
+use crate::handlers::easy_gnome::storage;
+use crate::handlers::easy_gnome::trash;
+use crate::handlers::easy_gnome::window;
+use crate::handlers::easy_gnome::wm;
+use crate::handlers::easy_gnome::workspaces;
 #[test]
 fn get_all_apps() {
     gtk::init().unwrap();
     let apps = easy_gnome::apps::Apps::new();
     assert!(apps.get_apps().len() > 0);
 }
+#[test]
+fn flatpak_apps_have_ids() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    for app in apps.get_apps() {
+        if app.is_flatpak() {
+            assert!(app.flatpak_id().unwrap().contains('.'));
+        }
+    }
+}
+#[test]
+fn icon_bytes_in_every_format() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let app = apps
+        .get_apps()
+        .iter()
+        .find(|app| app.icon.is_some())
+        .unwrap();
+    let png = app.get_icon_bytes(ImageFormat::Png).unwrap();
+    let webp = app.get_icon_bytes(ImageFormat::WebP).unwrap();
+    let jpeg = app.get_icon_bytes(ImageFormat::Jpeg).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+    assert!(webp.starts_with(b"RIFF"));
+    assert!(jpeg.starts_with(&[0xFF, 0xD8]));
+    assert!(app
+        .get_base64_icon_as(ImageFormat::WebP)
+        .unwrap()
+        .starts_with("data:image/webp;base64,"));
+}
+#[test]
+fn icon_at_other_sizes() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let app = apps
+        .get_apps()
+        .iter()
+        .find(|app| app.icon.is_some())
+        .unwrap();
+    let small = app.icon_at_size(32).unwrap();
+    assert_eq!(small.dimensions(), (32, 32));
+    assert_eq!(app.icon_at_size(32), Some(small));
+}
+#[test]
+fn icon_paths_exist() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let paths: Vec<_> = apps
+        .get_apps()
+        .iter()
+        .filter_map(|app| app.icon_path())
+        .collect();
+    assert!(!paths.is_empty());
+    assert!(paths.iter().all(|path| path.exists()));
+}
+#[test]
+fn apps_for_mime() {
+    gtk::init().unwrap();
+    assert!(easy_gnome::apps::default_for_mime("text/plain").is_some());
+    assert!(!easy_gnome::apps::all_for_mime("text/plain").is_empty());
+    assert!(easy_gnome::apps::all_for_mime("x-unknown/nothing").is_empty());
+}
+#[test]
+fn open_home_folder() {
+    easy_gnome::apps::open_path(&gio::glib::home_dir()).unwrap();
+}
+#[test]
+fn search_apps() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let found = apps.search("SETTINGS");
+    assert!(!found.is_empty());
+    assert!(apps.search("no app is called like this").is_empty());
+}
+struct EchoProvider;
+impl search_provider::SearchProvider for EchoProvider {
+    fn initial_result_set(&self, terms: &[String]) -> Vec<String> {
+        terms.to_vec()
+    }
+    fn result_metas(&self, ids: &[String]) -> Vec<search_provider::ResultMeta> {
+        ids.iter()
+            .map(|id| search_provider::ResultMeta {
+                id: id.clone(),
+                name: id.clone(),
+                description: None,
+                icon: None,
+            })
+            .collect()
+    }
+    fn activate_result(&self, _id: &str, _terms: &[String], _timestamp: u32) {}
+}
+#[tokio::test]
+async fn register_search_provider() {
+    let _server = search_provider::register(
+        "org.gnome.DbusApi.Test.SearchProvider",
+        "/org/gnome/DbusApi/Test/SearchProvider",
+        EchoProvider,
+    )
+    .await
+    .unwrap();
+    let connection = zbus::Connection::session().await.unwrap();
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.gnome.DbusApi.Test.SearchProvider",
+        "/org/gnome/DbusApi/Test/SearchProvider",
+        "org.gnome.Shell.SearchProvider2",
+    )
+    .await
+    .unwrap();
+    let terms = vec!["hello".to_string()];
+    let results: Vec<String> = proxy
+        .call("GetInitialResultSet", &(terms.clone(),))
+        .await
+        .unwrap();
+    assert_eq!(results, terms);
+}
+#[test]
+fn recent_files_round_trip() {
+    gtk::init().unwrap();
+    let uri = gio::File::for_path(gio::glib::home_dir()).uri();
+    assert!(easy_gnome::apps::add_recent_file(&uri));
+    let files = easy_gnome::apps::recent_files();
+    assert!(files.iter().any(|file| file.uri == uri.as_str()));
+    assert!(files.windows(2).all(|w| w[0].last_used >= w[1].last_used));
+    easy_gnome::apps::remove_recent_file(&uri).unwrap();
+    assert!(!easy_gnome::apps::recent_files()
+        .iter()
+        .any(|file| file.uri == uri.as_str()));
+}
+#[test]
+fn uninstall_native_app_is_not_supported() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let native = apps
+        .get_apps()
+        .iter()
+        .find(|app| !app.is_flatpak())
+        .unwrap();
+    assert!(matches!(
+        native.uninstall(),
+        Err(crate::error::Error::NotSupported)
+    ));
+}
 async fn pick_color() {
-    let (r, g, b) = screenshot::pick_color().await;
+    let (r, g, b) = screenshot::pick_color().await.unwrap();
 }
 #[tokio::test]
 async fn set_power_profile() {
     let power_profile = easy_gnome::PowerProfile::PowerSaver;
-    power::set_power_profile(power_profile).await;
-    assert_eq!(power::get_power_profile().await, power_profile);
+    power::set_power_profile(power_profile).await.unwrap();
+    assert_eq!(power::get_power_profile().await.unwrap(), power_profile);
 }
 
 #[tokio::test]
 async fn get_extensions() {
-    let extensions = extensions::get_extensions().await;
+    let extensions = extensions::get_extensions().await.unwrap();
     assert!(extensions.len() > 0);
     println!("{:?}", extensions);
 }
 #[tokio::test]
 async fn launch_extension_preferences() {
-    let _extensions_list = extensions::get_extensions().await;
+    let _extensions_list = extensions::get_extensions().await.unwrap();
     // You can get the extension uuid from the extensions::get_extensions() function
     let extension_uuid = "ubuntu-appindicators@ubuntu.com";
-    extensions::open_extension_preferences(extension_uuid).await
+    extensions::open_extension_preferences(extension_uuid)
+        .await
+        .unwrap();
 }
 #[tokio::test]
 async fn disable_extension() {
-    let _extensions_list = extensions::get_extensions().await;
+    let _extensions_list = extensions::get_extensions().await.unwrap();
     // You can get the extension uuid from the extensions::get_extensions() function
     let extension_uuid = "extension-list@tu.berry";
-    extensions::disable_extension(extension_uuid).await
+    extensions::disable_extension(extension_uuid).await.unwrap();
 }
 #[tokio::test]
 async fn enable_extension() {
-    let _extensions_list = extensions::get_extensions().await;
+    let _extensions_list = extensions::get_extensions().await.unwrap();
     // You can get the extension uuid from the extensions::get_extensions() function
     let extension_uuid = "extension-list@tu.berry";
-    extensions::enable_extension(extension_uuid).await
+    extensions::enable_extension(extension_uuid).await.unwrap();
 }
 #[tokio::test]
 async fn uninstall_extension() {
-    let _extensions_list = extensions::get_extensions().await;
+    let _extensions_list = extensions::get_extensions().await.unwrap();
     // You can get the extension uuid from the extensions::get_extensions() function
     let extension_uuid = "extension-list@tu.berry";
-    extensions::uninstall_extension(extension_uuid).await
+    extensions::uninstall_extension(extension_uuid)
+        .await
+        .unwrap();
 }
 
 #[test]
@@ -91,6 +264,25 @@ fn reset_keyboard_repeat_interval() {
         default_interval
     );
 }
+#[test]
+fn parse_string_array() {
+    let values = vec!["/org/custom0/".to_string(), "it's".to_string()];
+    let formatted = crate::dconf::format_string_array(&values);
+    assert_eq!(crate::dconf::parse_string_array(&formatted), values);
+    assert!(crate::dconf::parse_string_array("@as []").is_empty());
+}
+#[test]
+fn add_and_remove_custom_shortcut() {
+    let (shortcut, _conflicts) =
+        keybindings::add_custom_shortcut("Terminal", "gnome-terminal", "<Super>t").unwrap();
+    assert!(keybindings::list_custom_shortcuts()
+        .unwrap()
+        .contains(&shortcut));
+    keybindings::remove_custom_shortcut(&shortcut.path).unwrap();
+    assert!(!keybindings::list_custom_shortcuts()
+        .unwrap()
+        .contains(&shortcut));
+}
 #[tokio::test]
 async fn get_battery_display() {
     let battery_display = battery::get_current_device_battery().await.unwrap();
@@ -210,60 +402,1044 @@ fn reset_two_finger_scroll() {
 }
 
 async fn power_off() {
-    power::power_off().await;
+    power::power_off().await.unwrap();
 }
 async fn reboot() {
-    power::reboot().await;
+    power::reboot().await.unwrap();
 }
 async fn suspend() {
-    power::suspend().await;
+    power::suspend().await.unwrap();
 }
 
 #[test]
 fn get_temperature() {
-    let temperature: u32 = nightlight::get_temperature();
+    let temperature: u32 = nightlight::get_temperature().unwrap();
     println!("temperature: {}", temperature);
     assert!(temperature > 0);
 }
 #[test]
 fn set_temperature() {
     let temperature: u32 = 3000;
-    nightlight::set_temperature(temperature);
-    assert_eq!(nightlight::get_temperature(), temperature);
+    nightlight::set_temperature(temperature).unwrap();
+    assert_eq!(nightlight::get_temperature().unwrap(), temperature);
 }
 #[test]
 
 fn reset_temperature() {
     let temperature: u32 = 2700;
-    nightlight::reset_temperature();
-    assert_eq!(nightlight::get_temperature(), temperature);
+    nightlight::reset_temperature().unwrap();
+    assert_eq!(nightlight::get_temperature().unwrap(), temperature);
 }
 #[test]
 fn set_nightlight_active() {
     let active = true;
-    nightlight::set_nightlight_active(active);
-    assert_eq!(nightlight::get_nightlight_active(), active);
+    nightlight::set_nightlight_active(active).unwrap();
+    assert_eq!(nightlight::get_nightlight_active().unwrap(), active);
 }
 #[test]
 fn get_nightlight_active() {
     let active = false;
-    nightlight::set_nightlight_active(active);
-    assert_eq!(nightlight::get_nightlight_active(), active);
+    nightlight::set_nightlight_active(active).unwrap();
+    assert_eq!(nightlight::get_nightlight_active().unwrap(), active);
+}
+
+async fn brightness_up() -> crate::error::Result<()> {
+    screen::step_up().await
+}
+async fn brightness_down() -> crate::error::Result<()> {
+    screen::step_down().await
+}
+async fn get_brightness() -> crate::error::Result<i32> {
+    screen::brightness().await
+}
+async fn set_brightness(value: i32) -> crate::error::Result<()> {
+    screen::set_brightness(value).await
 }
 
-async fn brightness_up() -> Result<(), String> {
-    screen::step_up().await;
-    Ok(())
+#[tokio::test]
+async fn get_gnome_version() {
+    let version = session::gnome_version().await;
+    println!("version: {:?}", version);
+    assert!(version.is_some());
+}
+#[test]
+fn get_session_type() {
+    assert!(session::is_gnome());
+    assert_ne!(session::session_type(), session::SessionType::Unknown);
+}
+#[tokio::test]
+async fn eval() {
+    if !shell::is_eval_allowed().await {
+        return;
+    }
+    let (success, result) = shell::eval("1 + 1").await.unwrap();
+    assert!(success);
+    assert_eq!(result, "2");
+}
+#[tokio::test]
+async fn toggle_overview() {
+    shell::show_overview().await.unwrap();
+    assert!(shell::is_overview_active().await.unwrap());
+    shell::toggle_overview().await.unwrap();
+    assert!(!shell::is_overview_active().await.unwrap());
+}
+#[tokio::test]
+async fn get_active_window() {
+    let active_window = window::active_window().await.unwrap();
+    println!("{:?}", active_window);
+}
+#[tokio::test]
+async fn list_windows() {
+    let windows = window::list_windows().await.unwrap();
+    println!("{:?}", windows);
+}
+#[tokio::test]
+async fn activate_window() {
+    let windows = window::list_windows().await.unwrap();
+    if let Some(last_window) = windows.last() {
+        window::activate(last_window.id).await.unwrap();
+        assert_eq!(
+            window::active_window().await.unwrap().map(|w| w.id),
+            Some(last_window.id)
+        );
+    }
+}
+#[test]
+fn set_input_volume() {
+    sound::set_input_volume(0.5).unwrap();
+    assert_eq!(sound::get_input_volume().unwrap(), 0.5);
+    sound::set_input_volume(2.0).unwrap();
+    assert_eq!(sound::get_input_volume().unwrap(), 1.0);
+}
+#[test]
+fn toggle_input_mute() {
+    let muted = sound::is_input_muted().unwrap();
+    sound::toggle_input_mute().unwrap();
+    assert_eq!(sound::is_input_muted().unwrap(), !muted);
+    sound::set_input_muted(muted).unwrap();
+}
+#[tokio::test]
+async fn set_airplane_mode() {
+    rfkill::set_airplane_mode(true).await.unwrap();
+    assert!(rfkill::get_airplane_mode().await.unwrap());
+    let radio_state = rfkill::get_radio_state().await.unwrap();
+    assert!(radio_state.bluetooth_blocked);
+    rfkill::set_airplane_mode(false).await.unwrap();
+    assert!(!rfkill::get_airplane_mode().await.unwrap());
+}
+#[test]
+fn set_clock_format() {
+    interface::set_clock_format(interface::ClockFormat::TwelveHours).unwrap();
+    assert_eq!(
+        interface::get_clock_format().unwrap(),
+        interface::ClockFormat::TwelveHours
+    );
+}
+#[test]
+fn reset_clock_format() {
+    interface::reset_clock_format().unwrap();
+    assert_eq!(
+        interface::get_clock_format().unwrap(),
+        interface::ClockFormat::TwentyFourHours
+    );
 }
-async fn brightness_down() -> Result<(), String> {
-    screen::step_down().await;
-    Ok(())
+#[test]
+fn set_clock_show_seconds() {
+    interface::set_clock_show_seconds(true).unwrap();
+    assert_eq!(interface::get_clock_show_seconds().unwrap(), true);
+}
+#[test]
+fn reset_clock_show_seconds() {
+    interface::reset_clock_show_seconds().unwrap();
+    assert_eq!(interface::get_clock_show_seconds().unwrap(), false);
 }
-async fn get_brightness() -> Result<i32, String> {
-    let brightness = screen::brightness().await;
-    Ok(brightness)
+#[test]
+fn set_animations_enabled() {
+    interface::set_animations_enabled(false).unwrap();
+    assert_eq!(interface::get_animations_enabled().unwrap(), false);
+    assert_eq!(interface::get_reduced_motion().unwrap(), true);
+}
+#[test]
+fn reset_animations_enabled() {
+    interface::reset_animations_enabled().unwrap();
+    assert_eq!(interface::get_animations_enabled().unwrap(), true);
+}
+#[test]
+fn set_high_contrast() {
+    accessibility::set_high_contrast(true).unwrap();
+    assert_eq!(accessibility::get_high_contrast().unwrap(), true);
+}
+#[test]
+fn reset_high_contrast() {
+    accessibility::reset_high_contrast().unwrap();
+    assert_eq!(accessibility::get_high_contrast().unwrap(), false);
+}
+#[test]
+fn set_large_text() {
+    accessibility::set_large_text(true).unwrap();
+    assert_eq!(
+        accessibility::get_text_scaling_factor().unwrap(),
+        accessibility::LARGE_TEXT_SCALING_FACTOR
+    );
+    accessibility::set_large_text(false).unwrap();
+    assert_eq!(accessibility::get_large_text().unwrap(), false);
+}
+#[test]
+fn set_screen_keyboard_enabled() {
+    accessibility::set_screen_keyboard_enabled(true).unwrap();
+    assert_eq!(accessibility::get_screen_keyboard_enabled().unwrap(), true);
+    accessibility::reset_screen_keyboard_enabled().unwrap();
+    assert_eq!(accessibility::get_screen_keyboard_enabled().unwrap(), false);
+}
+#[test]
+fn set_magnifier_factor() {
+    accessibility::set_magnifier_factor(2.5).unwrap();
+    assert_eq!(accessibility::get_magnifier_factor().unwrap(), 2.5);
+    assert!(accessibility::set_magnifier_factor(0.5).is_err());
+}
+#[test]
+fn reset_magnifier_factor() {
+    accessibility::reset_magnifier_factor().unwrap();
+    assert_eq!(accessibility::get_magnifier_factor().unwrap(), 2.0);
+}
+#[test]
+fn set_hot_corners_enabled() {
+    interface::set_hot_corners_enabled(false).unwrap();
+    assert_eq!(interface::get_hot_corners_enabled().unwrap(), false);
+}
+#[test]
+fn reset_hot_corners_enabled() {
+    interface::reset_hot_corners_enabled().unwrap();
+    assert_eq!(interface::get_hot_corners_enabled().unwrap(), true);
+}
+#[test]
+fn set_edge_tiling() {
+    interface::set_edge_tiling(false).unwrap();
+    assert_eq!(interface::get_edge_tiling().unwrap(), false);
+    interface::reset_edge_tiling().unwrap();
+    assert_eq!(interface::get_edge_tiling().unwrap(), true);
+}
+#[test]
+fn set_dynamic_workspaces() {
+    interface::set_dynamic_workspaces(false).unwrap();
+    assert_eq!(interface::get_dynamic_workspaces().unwrap(), false);
+    interface::reset_dynamic_workspaces().unwrap();
+    assert_eq!(interface::get_dynamic_workspaces().unwrap(), true);
+}
+#[test]
+fn schedule_temperature_at() {
+    let schedule = nightlight::NightLightSchedule {
+        from: 20.0,
+        to: 6.0,
+        temperature: 2700,
+    };
+    assert_eq!(
+        schedule.temperature_at(12.0),
+        nightlight::DEFAULT_TEMPERATURE
+    );
+    assert_eq!(schedule.temperature_at(19.5), 4600);
+    assert_eq!(schedule.temperature_at(23.0), 2700);
+    assert_eq!(schedule.temperature_at(2.0), 2700);
+    assert_eq!(schedule.temperature_at(5.5), 4600);
+    assert_eq!(
+        schedule.temperature_at(6.0),
+        nightlight::DEFAULT_TEMPERATURE
+    );
+}
+#[test]
+fn automatic_schedule() {
+    nightlight::set_schedule_automatic(true).unwrap();
+    let schedule = nightlight::get_schedule().unwrap();
+    assert!((0.0..24.0).contains(&schedule.from));
+    assert!((0.0..24.0).contains(&schedule.to));
+    nightlight::reset_schedule_automatic().unwrap();
+}
+#[test]
+fn battery_thresholds_crossed() {
+    let thresholds = [20, 10];
+    let mut armed = [true, true];
+    let mut crossed = |percentage: f64, discharging: bool| {
+        battery::crossed_thresholds(&mut armed, &thresholds, percentage, discharging)
+    };
+    assert_eq!(crossed(25.0, true), vec![]);
+    assert_eq!(crossed(20.0, true), vec![20]);
+    assert_eq!(crossed(15.0, true), vec![]);
+    // Plugged in, nothing fires
+    assert_eq!(crossed(9.0, false), vec![]);
+    assert_eq!(crossed(9.0, true), vec![10]);
+    // Back above 20 re-arms both
+    assert_eq!(crossed(21.0, false), vec![]);
+    assert_eq!(crossed(19.0, true), vec![20]);
+}
+#[tokio::test]
+async fn watch_battery_thresholds() {
+    let thresholds = battery::watch_thresholds(&[20, 10]).await;
+    assert!(thresholds.is_ok());
+}
+#[tokio::test]
+async fn get_critical_action() {
+    let critical_action = battery::get_critical_action().await.unwrap();
+    let on_battery = battery::on_battery().await.unwrap();
+    println!(
+        "critical_action: {:?} on_battery: {}",
+        critical_action, on_battery
+    );
+}
+#[tokio::test]
+async fn watch_sleep() {
+    let sleep_events = power::watch_sleep().await;
+    assert!(sleep_events.is_ok());
+}
+#[tokio::test]
+async fn inhibit_suspend() {
+    let guard =
+        power::inhibit_suspend("gnome-dbus-api", "Running tests", power::InhibitMode::Sleep)
+            .await
+            .unwrap();
+    guard.release();
+}
+#[tokio::test]
+async fn watch_brightness() {
+    use futures_util::StreamExt;
+    let brightness = screen::watch_brightness().await.unwrap();
+    futures_util::pin_mut!(brightness);
+    let current = screen::brightness().await.unwrap();
+    screen::set_brightness(current / 2).await.unwrap();
+    assert_eq!(brightness.next().await, Some(current / 2));
+    screen::set_brightness(current).await.unwrap();
+}
+#[test]
+fn set_icon_theme() {
+    let themes = interface::list_installed_icon_themes();
+    assert!(themes.len() > 0);
+    interface::set_icon_theme(&themes[0]).unwrap();
+    assert_eq!(interface::get_icon_theme().unwrap(), themes[0]);
+    interface::reset_icon_theme().unwrap();
+}
+#[test]
+fn set_cursor_theme() {
+    let themes = interface::list_installed_cursor_themes();
+    assert!(themes.len() > 0);
+    interface::set_cursor_theme(&themes[0]).unwrap();
+    assert_eq!(interface::get_cursor_theme().unwrap(), themes[0]);
+    interface::reset_cursor_theme().unwrap();
+}
+#[test]
+fn list_gtk_themes() {
+    let themes = interface::list_gtk_themes();
+    println!("gtk themes: {:?}", themes);
+    assert!(themes.len() > 0);
+    println!("shell themes: {:?}", interface::list_shell_themes());
+}
+#[tokio::test]
+async fn record_screencast() {
+    let recording = screencast::start("gnome-dbus-api-test").await.unwrap();
+    assert!(screencast::start("gnome-dbus-api-test").await.is_err());
+    let path = recording.path().to_string();
+    recording.stop().await.unwrap();
+    assert!(std::path::Path::new(&path).exists());
+    std::fs::remove_file(path).unwrap();
+}
+#[tokio::test]
+async fn brightness_controller_coalesces() {
+    let controller =
+        screen::BrightnessController::new(screen::DEFAULT_BRIGHTNESS_INTERVAL).unwrap();
+    for brightness in 0..=60 {
+        controller.set(brightness);
+    }
+    drop(controller);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert_eq!(screen::brightness().await.unwrap(), 60);
+}
+#[tokio::test]
+async fn fade_brightness_supersedes() {
+    let dim = screen::fade_brightness(10, std::time::Duration::from_secs(2)).unwrap();
+    let restore = screen::fade_brightness(80, std::time::Duration::from_millis(300)).unwrap();
+    dim.wait();
+    restore.wait();
+    assert_eq!(screen::brightness().await.unwrap(), 80);
+}
+#[tokio::test]
+async fn set_gamma_temperature() {
+    gamma::set_color_temperature(3500).await.unwrap();
+    gamma::reset().await.unwrap();
+}
+#[tokio::test]
+async fn take_quiet_screenshot() {
+    let path = screenshot::take_screenshot_quiet("/tmp/gnome-dbus-api-test.png")
+        .await
+        .unwrap();
+    assert!(std::path::Path::new(&path).exists());
+    std::fs::remove_file(path).unwrap();
+}
+#[tokio::test]
+async fn list_monitors() {
+    let monitors = display::list_monitors().await.unwrap();
+    assert!(monitors.iter().any(|monitor| monitor.primary));
+}
+#[tokio::test]
+async fn capture_monitor() {
+    let png = screenshot::capture_monitor(0).await.unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+    assert!(screenshot::capture_monitor(usize::MAX).await.is_err());
+}
+#[tokio::test]
+async fn capture_area_to_clipboard() {
+    gtk::init().unwrap();
+    let area = screenshot::Rect {
+        x: 0,
+        y: 0,
+        width: 100,
+        height: 100,
+    };
+    screenshot::capture_to_clipboard(Some(area)).await.unwrap();
+    let png = clipboard::get_image().unwrap().unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+}
+#[tokio::test]
+async fn list_sessions() {
+    let sessions = session::list_sessions().await.unwrap();
+    assert!(sessions.iter().any(|session| session.active));
+}
+#[tokio::test]
+async fn power_capabilities() {
+    assert_ne!(
+        power::can_reboot().await.unwrap(),
+        power::PowerCapability::NotAvailable
+    );
+    power::can_suspend().await.unwrap();
+    power::can_power_off().await.unwrap();
+    power::can_hibernate().await.unwrap();
+}
+#[cfg(feature = "blocking")]
+#[test]
+fn blocking_battery_and_brightness() {
+    use crate::blocking;
+    blocking::battery::on_battery().unwrap();
+    let brightness = blocking::screen::brightness().unwrap();
+    blocking::screen::set_brightness(brightness).unwrap();
+}
+#[test]
+fn solar_times_in_madrid() {
+    let date = gio::glib::DateTime::from_utc(2023, 6, 21, 12, 0, 0.0).unwrap();
+    let (sunrise, sunset) = nightlight::solar_times(40.4, -3.7, &date).unwrap();
+    // 04:45 and 19:48 UTC on the summer solstice
+    assert!((sunrise - 4.75).abs() < 0.1);
+    assert!((sunset - 19.8).abs() < 0.1);
+    let polar_night = gio::glib::DateTime::from_utc(2023, 12, 21, 12, 0, 0.0).unwrap();
+    assert_eq!(nightlight::solar_times(80.0, 0.0, &polar_night), None);
 }
-async fn set_brightness(value: i32) -> Result<(), String> {
-    screen::set_brightness(value).await;
-    Ok(())
+#[tokio::test]
+async fn get_location() {
+    match location::get_location().await {
+        Ok(location) => assert!((-90.0..=90.0).contains(&location.latitude)),
+        Err(error) => assert!(matches!(error, crate::error::Error::LocationDenied)),
+    }
+}
+#[test]
+fn privacy_toggles() {
+    privacy::set_camera_enabled(false).unwrap();
+    assert_eq!(privacy::get_camera_enabled().unwrap(), false);
+    privacy::reset_camera_enabled().unwrap();
+    assert_eq!(privacy::get_camera_enabled().unwrap(), true);
+    privacy::set_old_files_age(7).unwrap();
+    assert_eq!(privacy::get_old_files_age().unwrap(), 7);
+    privacy::reset_old_files_age().unwrap();
+}
+#[test]
+fn trash_a_file() {
+    let path = gio::glib::home_dir().join("gnome-dbus-api-trash-test.txt");
+    std::fs::write(&path, "trash me").unwrap();
+    let (count, bytes) = trash::trash_size().unwrap();
+    trash::trash_item(&path).unwrap();
+    assert!(!path.exists());
+    let (new_count, new_bytes) = trash::trash_size().unwrap();
+    assert_eq!(new_count, count + 1);
+    assert_eq!(new_bytes, bytes + 8);
+}
+#[test]
+fn static_workspaces() {
+    workspaces::set_dynamic(false).unwrap();
+    workspaces::set_count(3).unwrap();
+    assert_eq!(workspaces::get_dynamic().unwrap(), false);
+    assert_eq!(workspaces::get_count().unwrap(), 3);
+    workspaces::reset_count().unwrap();
+    workspaces::reset_dynamic().unwrap();
+}
+#[test]
+fn window_buttons_layout() {
+    let mut layout = interface::ButtonLayout::parse("appmenu:minimize,maximize,close");
+    assert_eq!(layout.left, vec![interface::Button::AppMenu]);
+    layout.set_button(interface::Button::Maximize, false);
+    assert_eq!(layout.to_string(), "appmenu:minimize,close");
+    interface::set_window_buttons_layout(&layout).unwrap();
+    assert_eq!(interface::get_window_buttons_layout().unwrap(), layout);
+    interface::reset_window_buttons_layout().unwrap();
+}
+#[test]
+fn focus_follows_mouse() {
+    wm::set_focus_mode(wm::FocusMode::Sloppy).unwrap();
+    assert_eq!(wm::get_focus_mode().unwrap(), wm::FocusMode::Sloppy);
+    wm::reset_focus_mode().unwrap();
+    assert_eq!(wm::get_focus_mode().unwrap(), wm::FocusMode::Click);
+}
+#[test]
+fn accent_color_rgb() {
+    interface::set_accent_color(interface::AccentColor::Blue).unwrap();
+    assert_eq!(interface::accent_color_rgb().unwrap().to_hex(), "#3584e4");
+    interface::reset_accent_color().unwrap();
+}
+#[test]
+fn settings_snapshot_round_trip() {
+    let snapshot = settings::export();
+    assert!(snapshot.interface.gtk_theme.is_some());
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored: settings::SettingsSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, snapshot);
+    settings::import(&restored).unwrap();
+    assert_eq!(settings::export(), snapshot);
+}
+#[test]
+fn apply_theme_presets() {
+    interface::apply_dark_preset().unwrap();
+    assert_eq!(
+        interface::get_color_scheme().unwrap(),
+        interface::ColorScheme::PreferDark
+    );
+    assert_eq!(interface::get_gtk_theme().unwrap(), "Adwaita-dark");
+    interface::apply_light_preset().unwrap();
+    assert_eq!(interface::get_gtk_theme().unwrap(), "Adwaita");
+    interface::reset_color_scheme().unwrap();
+    interface::reset_gtk_theme().unwrap();
+    interface::reset_icon_theme().unwrap();
+}
+#[test]
+fn apply_preset_reports_failed_keys() {
+    // gsettings can't even be started with a NUL byte in an argument, an unknown theme
+    // name is still a valid string
+    let failed = interface::apply_preset(&interface::Preset {
+        color_scheme: interface::ColorScheme::Default,
+        gtk_theme: "Adwaita\0".to_string(),
+        icon_theme: "no-such-theme".to_string(),
+    });
+    assert_eq!(failed, Err(vec!["gtk-theme".to_string()]));
+    assert_eq!(interface::get_icon_theme().unwrap(), "no-such-theme");
+    interface::reset_color_scheme().unwrap();
+    interface::reset_icon_theme().unwrap();
+}
+#[tokio::test]
+async fn services_available() {
+    assert!(easy_gnome::service_available(easy_gnome::Bus::Session, "org.freedesktop.DBus").await);
+    assert!(!easy_gnome::service_available(easy_gnome::Bus::System, "org.example.Nothing").await);
+    assert!(!easy_gnome::service_available(easy_gnome::Bus::Session, "not a name").await);
+    assert!(extensions::is_shell_available().await);
+}
+#[tokio::test]
+async fn retry_only_transient_errors() {
+    use crate::retry::{set_retry_policy, with_retry, RetryPolicy};
+    use std::cell::Cell;
+    set_retry_policy(RetryPolicy {
+        max_attempts: 3,
+        initial_delay: std::time::Duration::from_millis(1),
+        max_delay: std::time::Duration::from_millis(1),
+    });
+    let attempts = Cell::new(0);
+    let result: zbus::Result<()> = with_retry(|| async {
+        attempts.set(attempts.get() + 1);
+        Err(zbus::fdo::Error::ServiceUnknown("restarting".to_string()).into())
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(attempts.get(), 3);
+
+    attempts.set(0);
+    let result: zbus::Result<()> = with_retry(|| async {
+        attempts.set(attempts.get() + 1);
+        Err(zbus::fdo::Error::AccessDenied("no".to_string()).into())
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(attempts.get(), 1);
+    set_retry_policy(RetryPolicy::DEFAULT);
+}
+#[tokio::test]
+async fn extension_settings() {
+    assert!(matches!(
+        extensions::settings("no-such-extension@example.com"),
+        Err(crate::error::Error::NotSupported)
+    ));
+    for extension in extensions::get_extensions().await.unwrap() {
+        if let Ok(settings) = extensions::settings(&extension.uuid) {
+            for key in settings.keys() {
+                assert!(settings.get(&key).is_some());
+            }
+        }
+    }
+}
+#[tokio::test]
+async fn read_extension_metadata() {
+    for extension in extensions::get_extensions().await.unwrap() {
+        let metadata = extensions::read_metadata(&extension.uuid).unwrap();
+        assert_eq!(metadata.uuid, extension.uuid);
+        assert!(!metadata.shell_version.is_empty());
+    }
+    assert!(matches!(
+        extensions::read_metadata("no-such-extension@example.com"),
+        Err(crate::error::Error::NotSupported)
+    ));
+}
+#[tokio::test]
+async fn reload_shell_is_not_supported_on_wayland() {
+    if session::session_type() == session::SessionType::Wayland {
+        assert!(matches!(
+            shell::reload().await,
+            Err(crate::error::Error::NotSupported)
+        ));
+    }
+}
+#[test]
+fn mouse_button_modifier() {
+    wm::set_mouse_button_modifier("<Alt>").unwrap();
+    assert_eq!(wm::get_mouse_button_modifier().unwrap(), "<Alt>");
+    wm::reset_mouse_button_modifier().unwrap();
+    wm::set_resize_with_right_button(true).unwrap();
+    assert!(wm::get_resize_with_right_button().unwrap());
+    wm::reset_resize_with_right_button().unwrap();
+}
+#[test]
+fn non_default_keys() {
+    interface::set_clock_show_seconds(true).unwrap();
+    assert!(settings::non_default_keys()
+        .iter()
+        .any(
+            |(schema, key, value)| schema == "org.gnome.desktop.interface"
+                && key == "clock-show-seconds"
+                && value == "true"
+        ));
+    interface::reset_clock_show_seconds().unwrap();
+    assert!(!settings::non_default_keys()
+        .iter()
+        .any(|(_, key, _)| key == "clock-show-seconds"));
+}
+#[test]
+fn reset_all_peripherals() {
+    peripherals::set_mouse_natural_scroll(true).unwrap();
+    peripherals::set_keyboard_press_delay(300).unwrap();
+    peripherals::reset_all().unwrap();
+    assert_eq!(peripherals::get_mouse_natural_scroll().unwrap(), false);
+    assert_eq!(peripherals::get_keyboard_press_delay().unwrap(), 500);
+}
+#[test]
+fn read_lock_keys() {
+    match keyboard::lock_keys() {
+        Ok(state) => assert_eq!(keyboard::num_lock_on().unwrap(), state.num_lock),
+        Err(error) => assert!(matches!(error, crate::error::Error::NotSupported)),
+    }
+}
+#[test]
+fn set_lock_keys() {
+    if session::session_type() != session::SessionType::X11 {
+        assert!(matches!(
+            keyboard::set_num_lock(true),
+            Err(crate::error::Error::NotSupported)
+        ));
+        return;
+    }
+    let num_lock = keyboard::num_lock_on().unwrap();
+    keyboard::set_num_lock(!num_lock).unwrap();
+    assert_eq!(keyboard::num_lock_on().unwrap(), !num_lock);
+    keyboard::set_num_lock(num_lock).unwrap();
+}
+#[test]
+fn remember_numlock_state() {
+    peripherals::set_keyboard_remember_numlock_state(false).unwrap();
+    assert!(!peripherals::get_keyboard_remember_numlock_state().unwrap());
+    peripherals::reset_keyboard_remember_numlock_state().unwrap();
+}
+#[test]
+fn clipboard_history() {
+    gtk::init().unwrap();
+    let mut history = clipboard::History::new(2);
+    clipboard::set_text("first");
+    assert!(history.record());
+    assert!(!history.record());
+    clipboard::set_text("second");
+    history.record();
+    clipboard::set_text("third");
+    history.record();
+    assert_eq!(history.entries().len(), 2);
+    history.restore(1).unwrap();
+    assert_eq!(clipboard::get_text().unwrap(), "second");
+    assert_eq!(
+        history.entries()[0].content,
+        clipboard::ClipboardContent::Text("second".to_string())
+    );
+    history.clear();
+    assert!(history.entries().is_empty());
+}
+#[test]
+fn clipboard_best_image() {
+    gtk::init().unwrap();
+    let png = image_utils::encode(&image::RgbaImage::new(4, 4), ImageFormat::Png).unwrap();
+    clipboard::set_image(&png).unwrap();
+    assert!(clipboard::available_types()
+        .iter()
+        .any(|mime_type| mime_type == "image/png"));
+    let (data, mime_type) = clipboard::get_best_image().unwrap();
+    assert_eq!(mime_type, "image/png");
+    assert!(image::load_from_memory(&data).is_ok());
+    assert_eq!(clipboard::extension_for(&mime_type), "png");
+}
+#[test]
+fn thumbnail_keeps_aspect_ratio() {
+    let png = image_utils::encode(&image::RgbaImage::new(400, 200), ImageFormat::Png).unwrap();
+    let thumbnail = image_utils::thumbnail(&png, 100).unwrap();
+    let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (100, 50));
+    assert!(matches!(
+        image_utils::thumbnail(b"not an image", 100),
+        Err(crate::error::Error::Parse(_))
+    ));
+}
+#[test]
+fn primary_selection() {
+    gtk::init().unwrap();
+    clipboard::set_text("clipboard");
+    clipboard::set_primary_text("primary");
+    assert_eq!(clipboard::get_primary_text().unwrap(), "primary");
+    assert_eq!(clipboard::get_text().unwrap(), "clipboard");
+}
+#[tokio::test]
+async fn output_volume_with_osd() {
+    let volume = sound::get_output_volume().unwrap();
+    match sound::set_output_volume_with_osd(0.3).await {
+        Ok(()) | Err(crate::error::Error::NotSupported) => {}
+        Err(error) => panic!("{}", error),
+    }
+    assert_eq!(sound::get_output_volume().unwrap(), 0.3);
+    sound::set_output_volume(volume).unwrap();
+}
+#[tokio::test]
+async fn grab_media_keys() {
+    let keys = media_keys::grab("gnome-dbus-api-test").await;
+    assert!(keys.is_ok());
+}
+#[test]
+fn default_browser() {
+    gtk::init().unwrap();
+    let browser = match easy_gnome::apps::get_default_browser() {
+        Some(browser) => browser,
+        None => return,
+    };
+    let desktop_file = browser.desktop_file.clone().unwrap();
+    let app_id = desktop_file.file_name().unwrap().to_string_lossy();
+    easy_gnome::apps::set_default_browser(&app_id).unwrap();
+    assert_eq!(
+        easy_gnome::apps::get_default_browser()
+            .unwrap()
+            .desktop_file,
+        browser.desktop_file
+    );
+    assert!(easy_gnome::apps::set_default_mail("not-an-app.desktop").is_err());
+}
+#[test]
+fn cancel_scheduled_power_off() {
+    let scheduled = power::schedule(
+        power::PowerAction::PowerOff,
+        std::time::Duration::from_secs(60),
+    );
+    assert_eq!(scheduled.action(), power::PowerAction::PowerOff);
+    assert!(scheduled.remaining() > std::time::Duration::from_secs(50));
+    scheduled.cancel();
+    assert!(matches!(
+        scheduled.wait(),
+        Err(crate::error::Error::Cancelled)
+    ));
+}
+#[tokio::test]
+async fn lid_close_action() {
+    let action = power::get_lid_close_action().await.unwrap();
+    match power::set_lid_close_action(action).await {
+        Ok(()) | Err(crate::error::Error::AuthRequired) => {}
+        Err(crate::error::Error::Io(error))
+            if error.kind() == std::io::ErrorKind::PermissionDenied => {}
+        Err(error) => panic!("{}", error),
+    }
+}
+#[tokio::test]
+async fn orientation_lock() {
+    if !display::has_accelerometer().await {
+        assert!(matches!(
+            display::get_orientation_lock().await,
+            Err(crate::error::Error::NotSupported)
+        ));
+        return;
+    }
+    let locked = display::get_orientation_lock().await.unwrap();
+    display::set_orientation_lock(!locked).await.unwrap();
+    assert_eq!(display::get_orientation_lock().await.unwrap(), !locked);
+    display::set_orientation_lock(locked).await.unwrap();
+}
+#[test]
+fn tablet_left_handed() {
+    for tablet in tablet::list_tablets() {
+        let left_handed = tablet::get_left_handed(&tablet.id).unwrap();
+        tablet::set_left_handed(&tablet.id, !left_handed).unwrap();
+        assert_eq!(tablet::get_left_handed(&tablet.id).unwrap(), !left_handed);
+        tablet::set_left_handed(&tablet.id, left_handed).unwrap();
+    }
+}
+#[test]
+fn terminal_apps() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    assert!(apps
+        .get_apps()
+        .iter()
+        .all(|app| !app.is_terminal_app() || app.desktop_file.is_some()));
+}
+#[test]
+fn launch_with_environment() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let app = apps
+        .get_apps()
+        .iter()
+        .find(|app| app.get_name().as_str() == "Calculator");
+    if let Some(app) = app {
+        let env = std::collections::HashMap::from([("LANG".to_string(), "C".to_string())]);
+        app.launch_with_context(env, Some(std::env::temp_dir()))
+            .unwrap();
+    }
+}
+#[test]
+fn startup_wm_class() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    for app in apps.get_apps() {
+        if let Some(wm_class) = app.startup_wm_class() {
+            assert!(!wm_class.is_empty());
+        }
+    }
+}
+#[tokio::test]
+async fn app_is_running() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::new();
+    let windows = match window::list_windows().await {
+        Ok(windows) => windows,
+        Err(_) => return,
+    };
+    for window in windows {
+        let app = apps.get_apps().iter().find(|app| {
+            app.desktop_file
+                .as_ref()
+                .and_then(|file| file.file_name())
+                .map(|name| Some(name.to_string_lossy().to_string()) == window.app_id)
+                .unwrap_or(false)
+        });
+        if let Some(app) = app {
+            assert!(app.is_running().await);
+        }
+    }
+}
+#[tokio::test]
+async fn quick_settings_state() {
+    let state = quick_settings::read_all().await;
+    let do_not_disturb = state.do_not_disturb.unwrap();
+    quick_settings::set_do_not_disturb(!do_not_disturb).unwrap();
+    assert_eq!(
+        quick_settings::read_all().await.do_not_disturb,
+        Some(!do_not_disturb)
+    );
+    quick_settings::set_do_not_disturb(do_not_disturb).unwrap();
+}
+#[test]
+fn battery_saver_toggle() {
+    power::set_battery_saver(true).unwrap();
+    assert!(power::is_battery_saver_enabled());
+    power::set_battery_saver(false).unwrap();
+    assert!(!power::is_battery_saver_enabled());
+}
+#[test]
+fn nightlight_dark_mode_sync_toggle() {
+    nightlight::sync_with_dark_mode(true).unwrap();
+    assert!(nightlight::is_synced_with_dark_mode());
+    nightlight::sync_with_dark_mode(false).unwrap();
+    assert!(!nightlight::is_synced_with_dark_mode());
+}
+#[tokio::test]
+async fn display_state_cache() {
+    let mut state = display::DisplayState::new().await.unwrap();
+    let monitors = state.list_monitors().await.unwrap();
+    assert_eq!(state.list_monitors().await.unwrap(), monitors);
+    state.invalidate();
+    assert_eq!(state.list_monitors().await.unwrap(), monitors);
+}
+#[tokio::test]
+async fn color_management_active_profiles() {
+    let profiles = color_management::list_profiles().await.unwrap();
+    for device in color_management::list_devices().await.unwrap() {
+        if let Some(active) = color_management::get_active_profile(&device.id)
+            .await
+            .unwrap()
+        {
+            assert!(profiles.iter().any(|profile| profile.id == active.id));
+        }
+    }
+    assert!(color_management::get_active_profile("no-such-device")
+        .await
+        .is_err());
+}
+#[test]
+fn toggle_boolean_settings() {
+    let locate_pointer = interface::get_locate_pointer().unwrap();
+    assert_eq!(interface::toggle_locate_pointer().unwrap(), !locate_pointer);
+    assert_eq!(interface::get_locate_pointer().unwrap(), !locate_pointer);
+    assert_eq!(interface::toggle_locate_pointer().unwrap(), locate_pointer);
+
+    let dark_mode = quick_settings::get_dark_mode().unwrap();
+    assert_eq!(quick_settings::toggle_dark_mode().unwrap(), !dark_mode);
+    quick_settings::set_dark_mode(dark_mode).unwrap();
+}
+#[test]
+fn clipboard_watcher_detects_changes() {
+    gtk::init().unwrap();
+    let mut watcher = clipboard::ClipboardWatcher::new(std::time::Duration::from_millis(50));
+    assert_eq!(watcher.poll(), None);
+    clipboard::set_text("clipboard watcher test");
+    let snapshot = match watcher.poll() {
+        Some(clipboard::ClipboardEvent::Changed(snapshot)) => snapshot,
+        event => panic!("Unexpected event {:?}", event),
+    };
+    // Plain text copies have no image
+    assert_eq!(snapshot.image, None);
+    assert_eq!(
+        snapshot.content(),
+        Some(clipboard::ClipboardContent::Text(
+            "clipboard watcher test".to_string()
+        ))
+    );
+    assert_eq!(watcher.poll(), None);
+}
+#[tokio::test]
+async fn watch_system_status() {
+    use futures_util::StreamExt;
+    let mut statuses = Box::pin(status::watch_system().await.unwrap());
+    let current = statuses.next().await.unwrap();
+    assert_eq!(current.on_ac, Some(!battery::on_battery().await.unwrap()));
+}
+#[test]
+fn sound_default_output_device() {
+    let devices = sound::list_output_devices().unwrap();
+    let default = devices.iter().find(|device| device.is_default).unwrap();
+    sound::set_default_output(&default.id).unwrap();
+}
+#[test]
+fn sound_sink_inputs() {
+    let inputs = sound::list_sink_inputs().unwrap();
+    println!("sink inputs: {:?}", inputs);
+    assert!(sound::watch_sink_inputs().is_ok());
+}
+#[test]
+fn apps_builder_options() {
+    gtk::init().unwrap();
+    let all = easy_gnome::apps::Apps::builder()
+        .load_icons(false)
+        .include_hidden(true)
+        .build();
+    let shown = easy_gnome::apps::Apps::new();
+    assert!(all.get_apps().len() >= shown.get_apps().len());
+    assert!(all.get_apps().iter().all(|app| app.icon.is_none()));
+
+    let small = easy_gnome::apps::Apps::builder().icon_size(32).build();
+    for app in small.get_apps() {
+        if let Some(icon) = &app.icon {
+            assert!(icon.width() <= 32 && icon.height() <= 32);
+        }
+    }
+    let games = easy_gnome::apps::Apps::builder()
+        .only_categories(&["Game"])
+        .load_icons(false)
+        .build();
+    assert!(games.get_apps().len() <= shown.get_apps().len());
+}
+#[tokio::test]
+async fn apps_watch_install() {
+    use futures_util::StreamExt;
+    let mut changes = Box::pin(easy_gnome::apps::watch());
+    let dir = gio::glib::user_data_dir().join("applications");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("gnome-dbus-api-watch-test.desktop");
+    // Let the monitor start watching
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    std::fs::write(
+        &path,
+        "[Desktop Entry]\nType=Application\nName=Watch test\nExec=true\n",
+    )
+    .unwrap();
+    assert_eq!(changes.next().await, Some(easy_gnome::apps::AppsChanged));
+    std::fs::remove_file(&path).unwrap();
+}
+#[test]
+fn app_name_for_locale() {
+    gtk::init().unwrap();
+    let apps = easy_gnome::apps::Apps::builder().load_icons(false).build();
+    for app in apps.get_apps() {
+        // No translation exists for this locale, the untranslated name is used
+        assert!(!app.name_for_locale("xx_XX").is_empty());
+    }
+}
+#[tokio::test]
+async fn timeout_gives_up() {
+    let slow = crate::runtime::sleep(std::time::Duration::from_secs(5));
+    let result = crate::timeout::timeout(std::time::Duration::from_millis(10), slow).await;
+    assert!(matches!(result, Err(crate::error::Error::Timeout)));
+    let extensions = extensions::get_extensions_timeout(std::time::Duration::from_secs(10)).await;
+    assert!(extensions.is_ok());
+}
+#[tokio::test]
+async fn default_timeout() {
+    crate::timeout::set_default_timeout(Some(std::time::Duration::from_secs(2)));
+    assert_eq!(
+        crate::timeout::get_default_timeout(),
+        Some(std::time::Duration::from_secs(2))
+    );
+    assert!(!extensions::get_extensions().await.unwrap().is_empty());
+    crate::timeout::set_default_timeout(None);
+}
+#[tokio::test]
+async fn hostnames() {
+    let hostnames = session::get_hostnames().await.unwrap();
+    assert_eq!(session::get_hostname().await.unwrap(), hostnames.transient);
+    assert!(!hostnames.transient.is_empty());
+    // Setting it back is a no-op, or asks for authentication
+    match session::set_pretty_hostname(&hostnames.pretty).await {
+        Err(crate::error::Error::AuthRequired) | Ok(()) => {}
+        Err(error) => panic!("{}", error),
+    }
+}
+#[tokio::test]
+async fn storage() {
+    let filesystems = storage::list_filesystems().await.unwrap();
+    let root = filesystems
+        .iter()
+        .find(|filesystem| filesystem.mount_point.as_deref() == Some(std::path::Path::new("/")));
+    if let Some(root) = root {
+        assert!(root.used_bytes.unwrap() <= root.total_bytes);
+        assert!(storage::is_mounted(&root.device).await.unwrap());
+    }
+    assert!(!storage::list_block_devices().await.unwrap().is_empty());
+    assert!(matches!(
+        storage::is_mounted("/dev/not-a-device").await,
+        Err(crate::error::Error::InvalidArgument(_))
+    ));
+}
+#[test]
+fn gsettings_errors() {
+    let schema = "org.gnome.desktop.interface";
+    assert!(crate::dconf::set(schema, "no-such-key", "true").is_err());
+    assert!(crate::dconf::reset(schema, "no-such-key").is_err());
+    assert!(crate::dconf::get_bool(schema, "no-such-key").is_err());
+    assert!(crate::dconf::set(schema, "locate-pointer", "'not a boolean'").is_err());
 }
@@ -0,0 +1,62 @@
+//! # Timeouts
+//! zbus 3 has no method call timeout, a hung service (e.g. GNOME Shell stuck in a long
+//! frame) leaves D-Bus calls waiting for ever. libdbus and GDBus clients give up after 25
+//! seconds, which is still far too long for interactive use.
+//!
+//! Wrap any async function of the crate in `timeout` to give up earlier, dropping the future
+//! cancels it. The shell-facing functions also have `_timeout` variants, and some of their
+//! calls are bounded by `set_default_timeout`, see there for which.
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use futures_util::future::{self, Either};
+
+use crate::error::{Error, Result};
+use crate::runtime::sleep;
+
+/// Runs `future` for at most `duration`, `Error::Timeout` after that.
+/// The timer runs on the runtime selected by the `async-io`/`tokio` feature.
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output> {
+    let sleep = sleep(duration);
+    futures_util::pin_mut!(future, sleep);
+    match future::select(future, sleep).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Error::Timeout),
+    }
+}
+
+static DEFAULT_TIMEOUT: RwLock<Option<Duration>> = RwLock::new(None);
+
+/// ## Default timeout of the GNOME Shell calls
+/// A process-wide setting, not one of the D-Bus connection (zbus 3 has none). It bounds
+/// these calls only, made from now on:
+/// - `extensions::get_extensions`, `enable_extension`, `disable_extension`,
+///   `uninstall_extension` and `open_extension_preferences`
+/// - `screenshot::take_screenshot`, `take_screenshot_quiet` and `take_screenshot_area`
+///
+/// They fail with `Error::Timeout` once it's over, each retry of the `RetryPolicy` gets the
+/// whole timeout. `screenshot::pick_color` waits for the user and isn't bounded, every other
+/// call of the crate isn't either: wrap them in `timeout`. `None`, the default, waits for ever.
+pub fn set_default_timeout(timeout: Option<Duration>) {
+    *DEFAULT_TIMEOUT.write().unwrap() = timeout;
+}
+pub fn get_default_timeout() -> Option<Duration> {
+    *DEFAULT_TIMEOUT.read().unwrap()
+}
+
+/// Runs a D-Bus call bounded by the default timeout, the error is then the `Timeout` one of
+/// the D-Bus spec, which converts to `Error::Timeout`
+pub(crate) async fn with_default_timeout<T, F>(call: F) -> zbus::Result<T>
+where
+    F: Future<Output = zbus::Result<T>>,
+{
+    match get_default_timeout() {
+        Some(duration) => timeout(duration, call).await.unwrap_or_else(|_| {
+            Err(zbus::Error::FDO(Box::new(zbus::fdo::Error::Timeout(
+                "No reply before the default timeout".to_string(),
+            ))))
+        }),
+        None => call.await,
+    }
+}
@@ -0,0 +1,34 @@
+use futures_util::{Stream, StreamExt};
+use zbus::Proxy;
+
+/// ## Stream the new value of a property every time it changes
+/// The stream holds its own clone of the proxy, so it keeps working after the caller drops
+/// the proxy, and it stops listening as soon as the stream itself is dropped.
+/// Values that can't be converted to `T` are skipped.
+pub async fn watch_property<'a, T>(
+    proxy: &Proxy<'a>,
+    property_name: &'static str,
+) -> impl Stream<Item = T> + 'a
+where
+    T: TryFrom<zvariant::OwnedValue> + Unpin + 'a,
+    T::Error: Into<zbus::Error>,
+{
+    proxy
+        .receive_property_changed::<T>(property_name)
+        .await
+        .filter_map(|change| async move { change.get().await.ok() })
+}
+/// ## Stream the arguments of a signal every time it's emitted
+/// `T` is the signal body, a single type for one argument or a tuple for several.
+/// The match rule is removed from the bus when the stream is dropped.
+/// Signals whose body doesn't match `T` are skipped.
+pub async fn watch_signal<T>(
+    proxy: &Proxy<'_>,
+    signal_name: &'static str,
+) -> zbus::Result<impl Stream<Item = T>>
+where
+    T: serde::de::DeserializeOwned + zvariant::Type,
+{
+    let signals = proxy.receive_signal(signal_name).await?;
+    Ok(signals.filter_map(|message| async move { message.body::<T>().ok() }))
+}
@@ -0,0 +1,211 @@
+//! Runs the crate against mock services on a private `dbus-daemon`, so these tests pass
+//! without a GNOME session. Both `DBUS_SESSION_BUS_ADDRESS` and `DBUS_SYSTEM_BUS_ADDRESS`
+//! point to the private bus while a test runs.
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use gnome_dbus_api::handlers::easy_gnome::power::BatterySaverConfig;
+use gnome_dbus_api::handlers::easy_gnome::{extensions, power, ListExtensionState, PowerProfile};
+use zbus::{dbus_interface, Connection, ConnectionBuilder};
+use zvariant::OwnedValue;
+
+/// Tests share the bus environment variables, so they run one at a time
+static BUS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A private bus, killed when dropped
+struct MockBus {
+    daemon: Child,
+    _lock: MutexGuard<'static, ()>,
+}
+impl MockBus {
+    fn start() -> MockBus {
+        let lock = BUS_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("dbus-daemon must be installed");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        let address = address.trim();
+        std::env::set_var("DBUS_SESSION_BUS_ADDRESS", address);
+        std::env::set_var("DBUS_SYSTEM_BUS_ADDRESS", address);
+        MockBus {
+            daemon,
+            _lock: lock,
+        }
+    }
+    async fn serve<I: zbus::Interface>(&self, name: &str, path: &str, iface: I) -> Connection {
+        ConnectionBuilder::session()
+            .unwrap()
+            .name(name)
+            .unwrap()
+            .serve_at(path, iface)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+    }
+}
+impl Drop for MockBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+struct MockExtensions {
+    extensions: HashMap<String, HashMap<String, OwnedValue>>,
+}
+#[dbus_interface(name = "org.gnome.Shell.Extensions")]
+impl MockExtensions {
+    fn list_extensions(&self) -> HashMap<String, HashMap<String, OwnedValue>> {
+        self.extensions.clone()
+    }
+}
+
+fn mock_extension(name: &str, state: f64) -> HashMap<String, OwnedValue> {
+    let mut info = HashMap::new();
+    info.insert("name".to_string(), zvariant::Value::from(name).into());
+    info.insert(
+        "description".to_string(),
+        zvariant::Value::from("A mock extension").into(),
+    );
+    info.insert("version".to_string(), zvariant::Value::from("3").into());
+    info.insert("state".to_string(), zvariant::Value::from(state).into());
+    info.insert(
+        "url".to_string(),
+        zvariant::Value::from("https://example.org").into(),
+    );
+    info
+}
+
+struct MockPowerProfiles {
+    active_profile: String,
+}
+#[dbus_interface(name = "net.hadess.PowerProfiles")]
+impl MockPowerProfiles {
+    #[dbus_interface(property)]
+    fn active_profile(&self) -> String {
+        self.active_profile.clone()
+    }
+    #[dbus_interface(property)]
+    fn set_active_profile(&mut self, profile: String) {
+        self.active_profile = profile;
+    }
+}
+
+struct MockUPower {
+    on_battery: bool,
+}
+#[dbus_interface(name = "org.freedesktop.UPower")]
+impl MockUPower {
+    #[dbus_interface(property)]
+    fn on_battery(&self) -> bool {
+        self.on_battery
+    }
+}
+
+#[tokio::test]
+async fn get_extensions_from_mock_shell() {
+    let bus = MockBus::start();
+    let mut mock = MockExtensions {
+        extensions: HashMap::new(),
+    };
+    mock.extensions
+        .insert("dash@example.org".to_string(), mock_extension("Dash", 1.0));
+    mock.extensions.insert(
+        "clock@example.org".to_string(),
+        mock_extension("Clock", 2.0),
+    );
+    let _service = bus
+        .serve(
+            "org.gnome.Shell.Extensions",
+            "/org/gnome/Shell/Extensions",
+            mock,
+        )
+        .await;
+
+    let mut list = extensions::get_extensions().await.unwrap();
+    list.sort_by(|a, b| a.uuid.cmp(&b.uuid));
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].uuid, "clock@example.org");
+    assert_eq!(list[0].name, "Clock");
+    assert!(matches!(list[0].state, ListExtensionState::DISABLED));
+    assert_eq!(list[1].uuid, "dash@example.org");
+    assert_eq!(list[1].version, "3");
+    assert!(matches!(list[1].state, ListExtensionState::ENABLED));
+}
+
+#[tokio::test]
+async fn power_profile_from_mock_daemon() {
+    let bus = MockBus::start();
+    let mock = MockPowerProfiles {
+        active_profile: "balanced".to_string(),
+    };
+    let _service = bus
+        .serve(
+            "net.hadess.PowerProfiles",
+            "/net/hadess/PowerProfiles",
+            mock,
+        )
+        .await;
+
+    assert!(power::is_power_profiles_available().await);
+    assert_eq!(
+        power::get_power_profile().await.unwrap(),
+        PowerProfile::Balanced
+    );
+    power::set_power_profile(PowerProfile::PowerSaver)
+        .await
+        .unwrap();
+    assert_eq!(
+        power::get_power_profile().await.unwrap(),
+        PowerProfile::PowerSaver
+    );
+}
+
+#[tokio::test]
+async fn battery_saver_without_backlight() {
+    let bus = MockBus::start();
+    let profiles = MockPowerProfiles {
+        active_profile: "balanced".to_string(),
+    };
+    let _profiles = bus
+        .serve(
+            "net.hadess.PowerProfiles",
+            "/net/hadess/PowerProfiles",
+            profiles,
+        )
+        .await;
+    let upower = MockUPower { on_battery: true };
+    let _upower = bus
+        .serve("org.freedesktop.UPower", "/org/freedesktop/UPower", upower)
+        .await;
+
+    // No screen brightness service on the bus, the dimming is skipped
+    let guard = power::start_auto_battery_saver(BatterySaverConfig {
+        dim_brightness: Some(50),
+    })
+    .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while power::get_power_profile().await.unwrap() != PowerProfile::PowerSaver {
+        assert!(
+            Instant::now() < deadline,
+            "the power saver was never applied"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    drop(guard);
+    assert_eq!(
+        power::get_power_profile().await.unwrap(),
+        PowerProfile::Balanced
+    );
+}